@@ -0,0 +1,89 @@
+//! Benchmarks of the event pipeline.
+//!
+//! Run with `cargo bench`. `PerfBudget` gives a quicker in-game estimate of
+//! the same stages.
+
+use std::collections::HashMap;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use game_events_sdk::{Compression, GameEventsIOEvent, GameEventsIOEventBuilder};
+use serde_json::json;
+
+fn event(i: u64) -> GameEventsIOEvent {
+    GameEventsIOEventBuilder::default()
+        .event("level_completed")
+        .user_id("bench_user")
+        .session_id("bench_session")
+        .time(i)
+        .event_properties(HashMap::from([
+            ("level".to_string(), json!(i % 50)),
+            ("item_id".to_string(), json!("sword_of_a_thousand_truths")),
+            ("platform".to_string(), json!("steam_deck")),
+        ]))
+        .build()
+        .unwrap()
+}
+
+fn event_creation(c: &mut Criterion) {
+    c.bench_function("event_creation", |b| {
+        b.iter(|| {
+            GameEventsIOEventBuilder::default()
+                .event(black_box("level_completed"))
+                .user_id("bench_user")
+                .session_id("bench_session")
+                .build()
+                .unwrap()
+        })
+    });
+}
+
+fn property_insertion(c: &mut Criterion) {
+    c.bench_function("property_insertion", |b| {
+        b.iter_batched(
+            GameEventsIOEvent::default,
+            |mut event| {
+                event
+                    .event_properties
+                    .insert("level".to_string(), json!(black_box(12)));
+                event
+                    .event_properties
+                    .insert("item_id".to_string(), json!("sword_of_a_thousand_truths"));
+                event
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn serialization(c: &mut Criterion) {
+    let event = event(1);
+    c.bench_function("serialization", |b| {
+        b.iter(|| serde_json::to_vec(black_box(&event)).unwrap())
+    });
+}
+
+fn batch_assembly(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch_assembly");
+    for size in [10u64, 100, 1000] {
+        let batch: Vec<GameEventsIOEvent> = (0..size).map(event).collect();
+        for compression in [Compression::None, Compression::Gzip] {
+            let id = BenchmarkId::new(format!("{:?}", compression), size);
+            group.bench_with_input(id, &batch, |b, batch| {
+                b.iter(|| {
+                    let body = serde_json::to_vec(black_box(batch)).unwrap();
+                    compression.encode(body).unwrap()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    event_creation,
+    property_insertion,
+    serialization,
+    batch_assembly
+);
+criterion_main!(benches);
@@ -0,0 +1,9 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Message types of the gRPC transport (see `src/grpc.rs`)
+    #[cfg(feature = "grpc")]
+    tonic_build::configure()
+        .build_client(false)
+        .build_server(false)
+        .compile_protos(&["proto/ingest.proto"], &["proto"])?;
+    Ok(())
+}
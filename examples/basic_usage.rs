@@ -21,7 +21,7 @@ fn main() {
     // Example 2: Using GameEventsIOSession (recommended way)
     println!("2. Creating a session and logging events...");
     // New: Defaults to random UUIDs if not specified, but here we specify them
-    let mut session = GameEventsIOSession::new("rust_user_123", "rust_session_456");
+    let session = GameEventsIOSession::new("rust_user_123", "rust_session_456");
 
     // Set user properties for the session
     session.set_user_property("platform", json!("rust"));
@@ -62,7 +62,7 @@ fn main() {
     // Flush all events
     println!("\nSending events to backend...");
     match client.flush() {
-        Ok(response) => println!("✓ Success! Response: {}", response),
+        Ok(result) => println!("✓ Success! {} events accepted", result.accepted),
         Err(e) => eprintln!("✗ Error: {}", e),
     }
 
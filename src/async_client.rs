@@ -0,0 +1,378 @@
+//! Async client for tokio-based games and servers (feature `async`).
+//!
+//! Failed batches are handled like in `GameEventsIOClient`: retried with
+//! the `RetryPolicy`, split on 413, put back in the buffer on retryable
+//! errors and 401/403 (which also pause uploads), and dead-lettered when
+//! the backend refuses them for good.
+
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::retry::FailedBatch;
+use crate::transport::{self, FlushResult, TransportResponse};
+use crate::{
+    diagnostics, unix_now_ms, Compression, GameEventsIOError, GameEventsIOEvent, NetworkConfig,
+    RetryPolicy, WireVersion, DEFAULT_BACKEND_URL, MAX_DEAD_LETTERS,
+};
+
+/// game-events.io SDK client built on the async `reqwest::Client`
+///
+/// Not cloneable, so an event can never be sent twice; use `into_shared`
+/// to log from several tasks.
+#[derive(Debug, Builder)]
+#[builder(setter(into))]
+pub struct AsyncGameEventsIOClient {
+    /// API key for authentication
+    api_key: String,
+
+    /// Backend URL (default: https://api.game-events.io/v1/events)
+    #[builder(default = "DEFAULT_BACKEND_URL.to_string()")]
+    backend_url: String,
+
+    /// Compression of request bodies
+    #[builder(default)]
+    compression: Compression,
+
+    /// TLS, proxy and timeout settings (certificates are verified by default)
+    #[builder(default)]
+    network_config: NetworkConfig,
+
+    /// Payload format sent to the backend (default: V1)
+    #[builder(default)]
+    wire_version: WireVersion,
+
+    /// How failed batches are retried during a flush
+    #[builder(default)]
+    retry_policy: RetryPolicy,
+
+    /// HTTP client for making requests, `None` if it could not be built
+    #[builder(setter(skip))]
+    #[builder(default = "self.default_client()")]
+    client: Option<reqwest::Client>,
+
+    /// Buffered events waiting to be sent
+    #[builder(setter(skip))]
+    #[builder(default)]
+    events: Vec<GameEventsIOEvent>,
+
+    /// Events the backend refused for good
+    #[builder(setter(skip))]
+    #[builder(default)]
+    dead_letters: Vec<GameEventsIOEvent>,
+
+    /// Whether uploads are paused after a 401/403
+    #[builder(setter(skip))]
+    #[builder(default)]
+    paused: bool,
+}
+
+impl AsyncGameEventsIOClientBuilder {
+    fn default_client(&self) -> Option<reqwest::Client> {
+        self.network_config
+            .clone()
+            .unwrap_or_default()
+            .async_client()
+    }
+}
+
+impl AsyncGameEventsIOClient {
+    /// Create a new async GameEventsIO client
+    pub fn new(api_key: impl Into<String>) -> Self {
+        let api_key = api_key.into();
+        diagnostics::recover(
+            AsyncGameEventsIOClientBuilder::default()
+                .api_key(api_key.clone())
+                .build(),
+            "Failed to create AsyncGameEventsIOClient",
+            || Self {
+                api_key,
+                backend_url: DEFAULT_BACKEND_URL.to_string(),
+                compression: Compression::None,
+                network_config: NetworkConfig::default(),
+                wire_version: WireVersion::V1,
+                retry_policy: RetryPolicy::default(),
+                client: None,
+                events: Vec::new(),
+                dead_letters: Vec::new(),
+                paused: false,
+            },
+        )
+    }
+
+    /// Turn the client into a handle that can be cloned across tasks
+    pub fn into_shared(self) -> AsyncSharedClient {
+        AsyncSharedClient {
+            client: Arc::new(Mutex::new(self)),
+        }
+    }
+
+    /// Log an event (adds to buffer)
+    pub fn log_event(&mut self, event: GameEventsIOEvent) {
+        self.events.push(event);
+    }
+
+    /// Send all buffered events to the backend
+    ///
+    /// A failed batch is sorted out like in `GameEventsIOClient::flush`.
+    pub async fn flush(&mut self) -> Result<FlushResult, GameEventsIOError> {
+        let count = self.events.len();
+        self.flush_batch(count).await
+    }
+
+    /// Send events in batches (useful for large event counts)
+    pub async fn flush_batch(
+        &mut self,
+        batch_size: usize,
+    ) -> Result<FlushResult, GameEventsIOError> {
+        if self.events.is_empty() || batch_size == 0 {
+            return Ok(FlushResult::default());
+        }
+        if self.paused {
+            return Err(GameEventsIOError::Unauthorized);
+        }
+
+        let count = std::cmp::min(self.events.len(), batch_size);
+        let batch: Vec<GameEventsIOEvent> = self.events.drain(..count).collect();
+        self.deliver(batch).await
+    }
+
+    /// Send a batch, splitting it on 413, and sort out the events if it fails
+    async fn deliver(
+        &mut self,
+        batch: Vec<GameEventsIOEvent>,
+    ) -> Result<FlushResult, GameEventsIOError> {
+        // Halves of too large batches, sent in order (last one first out)
+        let mut pending = vec![batch];
+        let mut total = FlushResult::default();
+        let mut sent = 0;
+
+        while let Some(mut batch) = pending.pop() {
+            let err = match self.send_with_retries(&mut batch).await {
+                Ok(result) => {
+                    total.accepted += result.accepted;
+                    total.rejected += result.rejected;
+                    total
+                        .rejected_indices
+                        .extend(result.rejected_indices.into_iter().map(|i| i + sent));
+                    total.body = result.body;
+                    sent += batch.len();
+                    continue;
+                }
+                Err(err) => err,
+            };
+
+            if matches!(err, GameEventsIOError::PayloadTooLarge) && batch.len() > 1 {
+                let mut first = batch;
+                let second = first.split_off(first.len() / 2);
+                pending.push(second);
+                pending.push(first);
+                continue;
+            }
+
+            // Batches not sent yet stay in the buffer, behind the failed one
+            let failed = FailedBatch::sort(&err, batch);
+            let unsent = pending.into_iter().rev().flatten();
+            self.events
+                .splice(0..0, failed.requeue.into_iter().chain(unsent));
+            self.dead_letters.extend(failed.dead_letters);
+            let excess = self.dead_letters.len().saturating_sub(MAX_DEAD_LETTERS);
+            self.dead_letters.drain(..excess);
+            if failed.pause {
+                self.paused = true;
+                diagnostics::report(&err);
+            }
+            return Err(err);
+        }
+        Ok(total)
+    }
+
+    /// Send a batch, retrying as the retry policy allows
+    async fn send_with_retries(
+        &self,
+        batch: &mut [GameEventsIOEvent],
+    ) -> Result<FlushResult, GameEventsIOError> {
+        let mut attempt = 1;
+        loop {
+            let err = match self.send_batch(batch).await {
+                Ok(result) => return Ok(result),
+                Err(err) => err,
+            };
+            match self.retry_policy.next_delay(attempt, &err) {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Err(err),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Send a batch once
+    async fn send_batch(
+        &self,
+        batch: &mut [GameEventsIOEvent],
+    ) -> Result<FlushResult, GameEventsIOError> {
+        let upload_ms = unix_now_ms();
+        for event in batch.iter_mut() {
+            event.client_upload_time = Some(upload_ms / 1000);
+            event.client_upload_time_ms = Some(upload_ms);
+        }
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| GameEventsIOError::Transport("HTTP client unavailable".to_string()))?;
+        let body = self
+            .wire_version
+            .encode_batch(batch)
+            .map_err(std::io::Error::from)?;
+        let mut request = client
+            .post(&self.backend_url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(self.compression.encode(body)?);
+        if let Some(encoding) = self.compression.content_encoding() {
+            request = request.header(reqwest::header::CONTENT_ENCODING, encoding);
+        }
+        let response = request.send().await?;
+        let status = response.status().as_u16();
+        let retry_after = transport::parse_retry_after(response.headers());
+        let server_time = transport::parse_server_time(response.headers());
+        let body = response.text().await?;
+
+        FlushResult::from_response(
+            TransportResponse {
+                status,
+                body,
+                retry_after,
+                server_time,
+            },
+            batch.len(),
+        )
+    }
+
+    /// Get the number of buffered events
+    pub fn pending_events_count(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Get the TLS, proxy and timeout settings of the client's HTTP requests
+    pub fn network_config(&self) -> &NetworkConfig {
+        &self.network_config
+    }
+
+    /// Take the events the backend refused (other 4xx, invalid events)
+    pub fn take_dead_letters(&mut self) -> Vec<GameEventsIOEvent> {
+        std::mem::take(&mut self.dead_letters)
+    }
+
+    /// Whether uploads are paused because the backend rejected the API key
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Send again after uploads were paused by a 401/403
+    pub fn resume_uploads(&mut self) {
+        self.paused = false;
+    }
+}
+
+/// Cloneable handle to one async client; clones share its buffer
+///
+/// Each event is sent exactly once by whichever handle flushes.
+#[derive(Clone, Debug)]
+pub struct AsyncSharedClient {
+    client: Arc<Mutex<AsyncGameEventsIOClient>>,
+}
+
+impl AsyncSharedClient {
+    /// Log an event (adds to the shared buffer)
+    pub async fn log_event(&self, event: GameEventsIOEvent) {
+        self.lock().await.log_event(event);
+    }
+
+    /// Send all buffered events to the backend
+    ///
+    /// Other handles wait while the flush is in progress.
+    pub async fn flush(&self) -> Result<FlushResult, GameEventsIOError> {
+        self.lock().await.flush().await
+    }
+
+    /// Send up to `batch_size` buffered events
+    pub async fn flush_batch(&self, batch_size: usize) -> Result<FlushResult, GameEventsIOError> {
+        self.lock().await.flush_batch(batch_size).await
+    }
+
+    /// Get the number of buffered events
+    pub async fn pending_events_count(&self) -> usize {
+        self.lock().await.pending_events_count()
+    }
+
+    /// Access the client (e.g. to take dead letters or resume uploads)
+    pub async fn lock(&self) -> MutexGuard<'_, AsyncGameEventsIOClient> {
+        self.client.lock().await
+    }
+
+    /// Number of handles to this client
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameEventsIOEventBuilder;
+
+    #[tokio::test]
+    async fn test_async_event_buffering() {
+        let mut client = AsyncGameEventsIOClient::new("test_api_key");
+        assert_eq!(client.flush().await.unwrap(), FlushResult::default());
+
+        let event = GameEventsIOEventBuilder::default()
+            .event("test_event")
+            .user_id("user123")
+            .session_id("session456")
+            .build()
+            .unwrap();
+        client.log_event(event);
+        assert_eq!(client.pending_events_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_flush_keeps_events() {
+        let mut client = AsyncGameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .backend_url("http://127.0.0.1:9/v1/events")
+            .retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        for name in ["level_started", "level_completed"] {
+            let event = GameEventsIOEventBuilder::default()
+                .event(name)
+                .build()
+                .unwrap();
+            client.log_event(event);
+        }
+
+        let err = client.flush().await.unwrap_err();
+        assert!(err.is_retryable());
+        assert_eq!(client.pending_events_count(), 2);
+        assert!(client.take_dead_letters().is_empty());
+        assert!(!client.is_paused());
+    }
+
+    #[tokio::test]
+    async fn test_shared_handles_share_one_buffer() {
+        let shared = AsyncGameEventsIOClient::new("test_api_key").into_shared();
+        let handle = shared.clone();
+        for name in ["level_started", "level_completed"] {
+            let event = GameEventsIOEventBuilder::default()
+                .event(name)
+                .build()
+                .unwrap();
+            handle.log_event(event).await;
+        }
+        assert_eq!(shared.pending_events_count().await, 2);
+        assert_eq!(shared.handle_count(), 2);
+    }
+}
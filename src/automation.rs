@@ -0,0 +1,51 @@
+//! Tagging of automated traffic (CI bots, soak tests, speedrun tools).
+
+use crate::GameEventsIOEvent;
+
+/// Event property set to `true` on automated events
+pub const IS_AUTOMATED_PROPERTY: &str = "is_automated";
+
+/// Event property holding the reason the run is automated
+pub const AUTOMATION_REASON_PROPERTY: &str = "automation_reason";
+
+/// Event property asking the backend to keep the event out of player metrics
+pub const EXCLUDE_FROM_REPORTS_PROPERTY: &str = "exclude_from_reports";
+
+/// How automated events are treated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AutomationSuppression {
+    /// Send events tagged with `is_automated` and `automation_reason`
+    #[default]
+    TagOnly,
+
+    /// Also flag events with `exclude_from_reports` for the backend
+    ExcludeFromReports,
+
+    /// Drop automated events without sending them
+    DropLocally,
+}
+
+/// Tag an automated event, returning `false` if it should be dropped
+pub(crate) fn tag_event(
+    event: &mut GameEventsIOEvent,
+    reason: &str,
+    suppression: AutomationSuppression,
+) -> bool {
+    if suppression == AutomationSuppression::DropLocally {
+        return false;
+    }
+
+    let properties = &mut event.event_properties;
+    properties.insert(IS_AUTOMATED_PROPERTY.to_string(), serde_json::json!(true));
+    properties.insert(
+        AUTOMATION_REASON_PROPERTY.to_string(),
+        serde_json::json!(reason),
+    );
+    if suppression == AutomationSuppression::ExcludeFromReports {
+        properties.insert(
+            EXCLUDE_FROM_REPORTS_PROPERTY.to_string(),
+            serde_json::json!(true),
+        );
+    }
+    true
+}
@@ -0,0 +1,115 @@
+//! Correction of wrong device clocks.
+//!
+//! Player devices often have clocks that are minutes or hours off. Every
+//! response from the backend reports its time in the `Date` header; the
+//! client keeps the difference with the local clock as `clock_offset_ms`.
+//! With `GameEventsIOClientBuilder::correct_clock_skew`, uploads carry
+//! timestamps shifted by that offset. Buffered events keep their original
+//! timestamps, so a later, better offset still applies to them correctly.
+
+use std::time::Duration;
+
+use crate::GameEventsIOEvent;
+
+/// Offsets smaller than this are within the precision of the `Date` header and ignored
+pub const CLOCK_SKEW_TOLERANCE: Duration = Duration::from_secs(2);
+
+/// Backend clock minus local clock in milliseconds
+///
+/// `server_time` is in seconds, `local_ms` is the local time in
+/// milliseconds the request was sent at.
+pub(crate) fn offset_ms(server_time: u64, local_ms: u64) -> i64 {
+    (server_time as i64 * 1000).saturating_sub(local_ms as i64)
+}
+
+/// Copies of `batch` with all timestamps shifted by `offset_ms`
+pub(crate) fn corrected(batch: &[GameEventsIOEvent], offset_ms: i64) -> Vec<GameEventsIOEvent> {
+    batch
+        .iter()
+        .map(|event| {
+            let mut event = event.clone();
+            let time_ms = shift(event.time_ms.unwrap_or(event.time * 1000), offset_ms);
+            event.time = time_ms / 1000;
+            event.time_ms = event.time_ms.map(|_| time_ms);
+            let upload_ms = event
+                .client_upload_time_ms
+                .or(event.client_upload_time.map(|time| time * 1000))
+                .map(|time| shift(time, offset_ms));
+            event.client_upload_time = upload_ms.map(|time| time / 1000);
+            event.client_upload_time_ms = event.client_upload_time_ms.and(upload_ms);
+            event
+        })
+        .collect()
+}
+
+/// Whether an offset is large enough to correct
+pub(crate) fn exceeds_tolerance(offset_ms: i64) -> bool {
+    offset_ms.unsigned_abs() >= CLOCK_SKEW_TOLERANCE.as_millis() as u64
+}
+
+fn shift(time_ms: u64, offset_ms: i64) -> u64 {
+    (time_ms as i64).saturating_add(offset_ms).max(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        unix_now, GameEventsIOClientBuilder, GameEventsIOError, GameEventsIOEventBuilder,
+        MemoryTransport, Transport, TransportResponse,
+    };
+    use std::sync::Arc;
+
+    /// Records batches; the backend clock is an hour ahead
+    struct AheadTransport(MemoryTransport);
+
+    impl Transport for AheadTransport {
+        fn send(
+            &self,
+            batch: &[GameEventsIOEvent],
+        ) -> Result<TransportResponse, GameEventsIOError> {
+            let response = self.0.send(batch)?;
+            Ok(TransportResponse {
+                server_time: Some(unix_now() + 3600),
+                ..response
+            })
+        }
+    }
+
+    #[test]
+    fn test_sequence_and_skew_correction() {
+        let event = GameEventsIOEventBuilder::default()
+            .event("shot_fired")
+            .build()
+            .unwrap();
+        assert_eq!(event.time, event.time_ms.unwrap() / 1000);
+
+        let transport = Arc::new(AheadTransport(MemoryTransport::new()));
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .correct_clock_skew(true)
+            .build()
+            .unwrap();
+        for _ in 0..3 {
+            client.log_event(event.clone());
+        }
+        client.flush().unwrap();
+        let offset = client.clock_offset_ms().unwrap();
+        assert!((3_598_000..=3_601_000).contains(&offset), "{}", offset);
+
+        client.log_event(event.clone());
+        client.flush().unwrap();
+        let batches = transport.0.batches();
+        let seqs: Vec<_> = batches.concat().iter().map(|e| e.seq.unwrap()).collect();
+        assert_eq!(seqs, [0, 1, 2, 3]);
+
+        // The first batch went out before the offset was known
+        assert_eq!(batches[0][0].time_ms, event.time_ms);
+        let sent = &batches[1][0];
+        assert_eq!(sent.time_ms, Some(event.time_ms.unwrap() + offset as u64));
+        assert_eq!(sent.time, sent.time_ms.unwrap() / 1000);
+
+        assert!(!exceeds_tolerance(-1500));
+    }
+}
@@ -0,0 +1,257 @@
+//! Wire-format compatibility checks.
+//!
+//! Serializes a corpus of representative events and compares the result
+//! against golden payloads recorded by an earlier SDK version, so an SDK
+//! upgrade can't silently change what the backend receives.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::{GameEventsIOEvent, GameEventsIOSession, WireVersion};
+
+/// Fixed timestamp used by the built-in corpus
+pub const GOLDEN_TIME: u64 = 1_700_000_000;
+
+/// A single difference between a golden and a current payload
+#[derive(Clone, Debug, PartialEq)]
+pub enum PayloadDiff {
+    /// Field present in the golden payload but not in the current one
+    Missing { path: String, expected: Value },
+
+    /// Field present in the current payload but not in the golden one
+    Added { path: String, actual: Value },
+
+    /// Field present in both payloads with different values
+    Changed {
+        path: String,
+        expected: Value,
+        actual: Value,
+    },
+}
+
+impl fmt::Display for PayloadDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayloadDiff::Missing { path, expected } => {
+                write!(f, "{}: missing (expected {})", path, expected)
+            }
+            PayloadDiff::Added { path, actual } => write!(f, "{}: added ({})", path, actual),
+            PayloadDiff::Changed {
+                path,
+                expected,
+                actual,
+            } => write!(f, "{}: changed from {} to {}", path, expected, actual),
+        }
+    }
+}
+
+/// Golden comparison result for one corpus entry
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoldenMismatch {
+    /// Corpus entry name
+    pub name: String,
+
+    /// Differences found, empty if the golden file is missing
+    pub diffs: Vec<PayloadDiff>,
+
+    /// Whether the golden file did not exist
+    pub missing_golden: bool,
+}
+
+/// Compare two payloads, returning every difference found
+///
+/// Paths use JSON pointer syntax (e.g. `/event_properties/score`).
+pub fn diff_payloads(expected: &Value, actual: &Value) -> Vec<PayloadDiff> {
+    let mut diffs = Vec::new();
+    diff_at(String::new(), expected, actual, &mut diffs);
+    diffs
+}
+
+fn diff_at(path: String, expected: &Value, actual: &Value, diffs: &mut Vec<PayloadDiff>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, expected_value) in expected {
+                let child = format!("{}/{}", path, key);
+                match actual.get(key) {
+                    Some(actual_value) => diff_at(child, expected_value, actual_value, diffs),
+                    None => diffs.push(PayloadDiff::Missing {
+                        path: child,
+                        expected: expected_value.clone(),
+                    }),
+                }
+            }
+            for (key, actual_value) in actual {
+                if !expected.contains_key(key) {
+                    diffs.push(PayloadDiff::Added {
+                        path: format!("{}/{}", path, key),
+                        actual: actual_value.clone(),
+                    });
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+            for (index, (expected_value, actual_value)) in expected.iter().zip(actual).enumerate() {
+                diff_at(
+                    format!("{}/{}", path, index),
+                    expected_value,
+                    actual_value,
+                    diffs,
+                );
+            }
+        }
+        _ if expected != actual => diffs.push(PayloadDiff::Changed {
+            path: if path.is_empty() {
+                "/".to_string()
+            } else {
+                path
+            },
+            expected: expected.clone(),
+            actual: actual.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Representative events covering every part of the event payload
+pub fn golden_corpus() -> Vec<(&'static str, GameEventsIOEvent)> {
+    let event = |name: &str| GameEventsIOEvent {
+        event: name.to_string(),
+        user_id: "user_1".to_string(),
+        session_id: "session_1".to_string(),
+        time: GOLDEN_TIME,
+        ..GameEventsIOEvent::default()
+    };
+
+    let minimal = event("app_start");
+
+    let mut event_properties = event("level_completed");
+    event_properties.event_properties = HashMap::from([
+        ("level_id".to_string(), json!(5)),
+        ("score".to_string(), json!(1500)),
+        ("difficulty".to_string(), json!("hard")),
+    ]);
+
+    let mut user_properties = event("purchase");
+    user_properties.event_properties = HashMap::from([
+        ("item_id".to_string(), json!("sword_legendary")),
+        ("price".to_string(), json!(9.99)),
+        ("currency".to_string(), json!("USD")),
+    ]);
+    user_properties.user_properties = HashMap::from([
+        ("platform".to_string(), json!("rust")),
+        ("level".to_string(), json!(10)),
+    ]);
+
+    let mut nested_values = event("player_died");
+    nested_values.event_properties = HashMap::from([
+        ("tags".to_string(), json!(["boss", "night"])),
+        ("position".to_string(), json!({ "x": 1.5, "y": -2 })),
+        ("cleared".to_string(), json!(true)),
+        ("killer".to_string(), Value::Null),
+    ]);
+
+    let mut uploaded = event("app_start");
+    uploaded.client_upload_time = Some(GOLDEN_TIME + 30);
+
+    let session = GameEventsIOSession::new("user_1", "session_1");
+    let mut new_session = session.take_events(1).pop().unwrap_or_default();
+    new_session.time = GOLDEN_TIME;
+    new_session.time_ms = None;
+
+    vec![
+        ("minimal", minimal),
+        ("event_properties", event_properties),
+        ("user_properties", user_properties),
+        ("nested_values", nested_values),
+        ("uploaded", uploaded),
+        ("new_session", new_session),
+    ]
+}
+
+/// Compare a corpus against golden payloads stored as `<dir>/<name>.json`
+///
+/// Returns one entry per corpus event whose payload does not match.
+pub fn check_golden_dir(
+    dir: &Path,
+    corpus: &[(&str, GameEventsIOEvent)],
+) -> io::Result<Vec<GoldenMismatch>> {
+    let mut mismatches = Vec::new();
+
+    for (name, event) in corpus {
+        let actual = WireVersion::V1.to_value(event)?;
+        let path = dir.join(format!("{}.json", name));
+
+        let mismatch = match fs::read_to_string(&path) {
+            Ok(contents) => {
+                let expected: Value = serde_json::from_str(&contents)?;
+                let diffs = diff_payloads(&expected, &actual);
+                if diffs.is_empty() {
+                    continue;
+                }
+                GoldenMismatch {
+                    name: name.to_string(),
+                    diffs,
+                    missing_golden: false,
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => GoldenMismatch {
+                name: name.to_string(),
+                diffs: Vec::new(),
+                missing_golden: true,
+            },
+            Err(e) => return Err(e),
+        };
+        mismatches.push(mismatch);
+    }
+
+    Ok(mismatches)
+}
+
+/// Record the current payloads of a corpus as golden files in `dir`
+pub fn write_golden_dir(dir: &Path, corpus: &[(&str, GameEventsIOEvent)]) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for (name, event) in corpus {
+        let mut contents = serde_json::to_string_pretty(&WireVersion::V1.to_value(event)?)?;
+        contents.push('\n');
+        fs::write(dir.join(format!("{}.json", name)), contents)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_payloads() {
+        let payload = json!({ "event": "a", "event_properties": { "x": [1, 2] } });
+        assert!(diff_payloads(&payload, &payload.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_paths() {
+        let expected = json!({ "event": "a", "time": 1, "event_properties": { "x": 1 } });
+        let actual = json!({ "event": "a", "time_ms": 1000, "event_properties": { "x": "1" } });
+
+        let diffs = diff_payloads(&expected, &actual);
+        assert_eq!(diffs.len(), 3);
+        assert!(diffs.contains(&PayloadDiff::Missing {
+            path: "/time".to_string(),
+            expected: json!(1),
+        }));
+        assert!(diffs.contains(&PayloadDiff::Added {
+            path: "/time_ms".to_string(),
+            actual: json!(1000),
+        }));
+        assert!(diffs.contains(&PayloadDiff::Changed {
+            path: "/event_properties/x".to_string(),
+            expected: json!(1),
+            actual: json!("1"),
+        }));
+    }
+}
@@ -0,0 +1,134 @@
+//! Compression of request bodies.
+//!
+//! `Compression` covers the built-in codecs. Platforms with their own
+//! certified or hardware-accelerated codec implement `Compressor` and pass
+//! it to `GameEventsIOClientBuilder::compressor` or
+//! `HttpTransport::compressor`.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use flate2::write::GzEncoder;
+
+/// Codec applied to the body of each upload
+pub trait Compressor: Send + Sync {
+    /// Compress a request body
+    fn compress(&self, body: &[u8]) -> io::Result<Vec<u8>>;
+
+    /// Value of the `Content-Encoding` header, if any
+    fn content_encoding(&self) -> Option<&str>;
+}
+
+impl fmt::Debug for dyn Compressor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Compressor")
+            .field("content_encoding", &self.content_encoding())
+            .finish()
+    }
+}
+
+impl<C: Compressor + ?Sized> Compressor for Arc<C> {
+    fn compress(&self, body: &[u8]) -> io::Result<Vec<u8>> {
+        (**self).compress(body)
+    }
+
+    fn content_encoding(&self) -> Option<&str> {
+        (**self).content_encoding()
+    }
+}
+
+/// Compression applied to the JSON body of each upload
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// Send plain JSON
+    #[default]
+    None,
+
+    /// gzip, sent with `Content-Encoding: gzip`
+    Gzip,
+
+    /// zstd, sent with `Content-Encoding: zstd` (feature `zstd`)
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// Value of the `Content-Encoding` header, if any
+    pub fn content_encoding(self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gzip"),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Some("zstd"),
+        }
+    }
+
+    /// Compress a request body
+    pub fn encode(self, body: Vec<u8>) -> io::Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(body),
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&body)?;
+                encoder.finish()
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::encode_all(body.as_slice(), 0),
+        }
+    }
+}
+
+impl Compressor for Compression {
+    fn compress(&self, body: &[u8]) -> io::Result<Vec<u8>> {
+        self.encode(body.to_vec())
+    }
+
+    fn content_encoding(&self) -> Option<&str> {
+        Compression::content_encoding(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_gzip_round_trip() {
+        let body = br#"[{"event":"level_completed"}]"#.repeat(100);
+        let compressed = Compression::Gzip.encode(body.clone()).unwrap();
+        assert!(compressed.len() < body.len());
+
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+        assert_eq!(Compression::None.encode(body.clone()).unwrap(), body);
+    }
+
+    /// Stand-in for a platform codec
+    struct Reversed;
+
+    impl Compressor for Reversed {
+        fn compress(&self, body: &[u8]) -> io::Result<Vec<u8>> {
+            Ok(body.iter().rev().copied().collect())
+        }
+
+        fn content_encoding(&self) -> Option<&str> {
+            Some("x-reversed")
+        }
+    }
+
+    #[test]
+    fn test_custom_compressor() {
+        let codecs: [Arc<dyn Compressor>; 2] = [Arc::new(Compression::Gzip), Arc::new(Reversed)];
+        assert_eq!(codecs[0].content_encoding(), Some("gzip"));
+        assert_eq!(codecs[1].compress(b"abc").unwrap(), b"cba");
+
+        let shared = Arc::new(Reversed);
+        let transport = crate::HttpTransport::new("http://localhost", "").compressor(shared);
+        assert!(format!("{:?}", transport).contains("x-reversed"));
+    }
+}
@@ -0,0 +1,213 @@
+//! Device and platform context attached to events.
+//!
+//! `DeviceContext::detect` gathers what the SDK can find out on its own (OS,
+//! OS version, device model, locale, SDK version); the game adds its app
+//! version and build and overrides anything it knows better. Set it on a
+//! session as a `ContextProvider` and it is added to the user properties of
+//! every event. User properties set by the game take precedence.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// User property holding the operating system (e.g. "linux", "windows")
+pub const OS_PROPERTY: &str = "os";
+
+/// User property holding the operating system version
+pub const OS_VERSION_PROPERTY: &str = "os_version";
+
+/// User property holding the device model
+pub const DEVICE_MODEL_PROPERTY: &str = "device_model";
+
+/// User property holding the game's version
+pub const APP_VERSION_PROPERTY: &str = "app_version";
+
+/// User property holding the game's build number
+pub const APP_BUILD_PROPERTY: &str = "app_build";
+
+/// User property holding the player's locale (e.g. "en_US")
+pub const LOCALE_PROPERTY: &str = "locale";
+
+/// User property holding the version of this SDK
+pub const SDK_VERSION_PROPERTY: &str = "sdk_version";
+
+/// Device and app details attached to every event
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeviceContext {
+    /// Operating system
+    pub os: Option<String>,
+
+    /// Operating system version
+    pub os_version: Option<String>,
+
+    /// Device model
+    pub device_model: Option<String>,
+
+    /// Game version
+    pub app_version: Option<String>,
+
+    /// Game build number
+    pub app_build: Option<String>,
+
+    /// Player locale
+    pub locale: Option<String>,
+
+    /// SDK version
+    pub sdk_version: Option<String>,
+}
+
+impl DeviceContext {
+    /// Detect what the platform exposes through `std::env` and system files
+    pub fn detect() -> Self {
+        Self {
+            os: Some(std::env::consts::OS.to_string()),
+            os_version: detect_os_version(),
+            device_model: detect_device_model(),
+            app_version: None,
+            app_build: None,
+            locale: detect_locale(),
+            sdk_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        }
+    }
+
+    /// Set the game's version
+    pub fn app_version(mut self, version: impl Into<String>) -> Self {
+        self.app_version = Some(version.into());
+        self
+    }
+
+    /// Set the game's build number
+    pub fn app_build(mut self, build: impl Into<String>) -> Self {
+        self.app_build = Some(build.into());
+        self
+    }
+
+    /// Override the detected OS version
+    pub fn os_version(mut self, version: impl Into<String>) -> Self {
+        self.os_version = Some(version.into());
+        self
+    }
+
+    /// Override the detected device model (e.g. from a console SDK)
+    pub fn device_model(mut self, model: impl Into<String>) -> Self {
+        self.device_model = Some(model.into());
+        self
+    }
+
+    /// Override the detected locale (e.g. with the in-game language setting)
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Add the known details to a user property map, keeping existing values
+    pub fn apply(&self, user_properties: &mut HashMap<String, serde_json::Value>) {
+        let fields = [
+            (OS_PROPERTY, &self.os),
+            (OS_VERSION_PROPERTY, &self.os_version),
+            (DEVICE_MODEL_PROPERTY, &self.device_model),
+            (APP_VERSION_PROPERTY, &self.app_version),
+            (APP_BUILD_PROPERTY, &self.app_build),
+            (LOCALE_PROPERTY, &self.locale),
+            (SDK_VERSION_PROPERTY, &self.sdk_version),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value {
+                user_properties
+                    .entry(key.to_string())
+                    .or_insert_with(|| serde_json::json!(value));
+            }
+        }
+    }
+}
+
+/// Hook supplying the device context of a session
+pub trait ContextProvider: Send + Sync {
+    /// Gather the current device context
+    fn context(&self) -> DeviceContext;
+}
+
+impl ContextProvider for DeviceContext {
+    fn context(&self) -> DeviceContext {
+        self.clone()
+    }
+}
+
+impl<F> ContextProvider for F
+where
+    F: Fn() -> DeviceContext + Send + Sync,
+{
+    fn context(&self) -> DeviceContext {
+        self()
+    }
+}
+
+impl fmt::Debug for dyn ContextProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ContextProvider")
+    }
+}
+
+/// `VERSION_ID` from `/etc/os-release` on Linux
+fn detect_os_version() -> Option<String> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let release = std::fs::read_to_string("/etc/os-release").ok()?;
+    release.lines().find_map(|line| {
+        let value = line.strip_prefix("VERSION_ID=")?;
+        Some(value.trim_matches('"').to_string())
+    })
+}
+
+/// DMI product name on Linux (e.g. "Jupiter" on the Steam Deck)
+fn detect_device_model() -> Option<String> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let model = std::fs::read_to_string("/sys/devices/virtual/dmi/id/product_name").ok()?;
+    Some(model.trim().to_string()).filter(|model| !model.is_empty())
+}
+
+/// Locale from the POSIX environment, without the encoding (`en_US.UTF-8` -> `en_US`)
+fn detect_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|value| {
+            value
+                .split(['.', '@'])
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .find(|locale| !locale.is_empty() && locale != "C" && locale != "POSIX")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameEventsIOSessionBuilder;
+
+    #[test]
+    fn test_context_attached_without_overriding() {
+        let context = DeviceContext::detect().app_version("1.4.2").locale("ja_JP");
+        assert_eq!(context.os.as_deref(), Some(std::env::consts::OS));
+        assert_eq!(
+            context.sdk_version.as_deref(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+
+        let session = GameEventsIOSessionBuilder::default()
+            .context_provider(context)
+            .build()
+            .unwrap();
+        session.set_user_property("locale", serde_json::json!("fr_FR"));
+        session.push_event("level_started", HashMap::new());
+
+        let events = session.take_events(10);
+        let props = &events[1].user_properties;
+        assert_eq!(props[APP_VERSION_PROPERTY], "1.4.2");
+        assert_eq!(props[OS_PROPERTY], std::env::consts::OS);
+        assert_eq!(props[LOCALE_PROPERTY], "fr_FR");
+    }
+}
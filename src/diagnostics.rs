@@ -0,0 +1,142 @@
+//! Error reporting and the panic policy.
+//!
+//! Telemetry must never crash a shipped game. Errors the SDK can't return to
+//! the caller (background flushes, disk writes of the offline queue) go to a
+//! process-wide error callback. Internal failures that would otherwise
+//! panic follow the `PanicPolicy`: debug builds panic so bugs surface early,
+//! release builds report the error and continue with a fallback value.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, RwLock};
+
+use crate::GameEventsIOError;
+
+type ErrorCallback = Arc<dyn Fn(&GameEventsIOError) + Send + Sync>;
+
+static ERROR_CALLBACK: RwLock<Option<ErrorCallback>> = RwLock::new(None);
+
+/// 0 means "not set", see `PanicPolicy::default`
+static PANIC_POLICY: AtomicU8 = AtomicU8::new(0);
+
+/// Held by tests that change the error callback or the panic policy
+#[cfg(test)]
+pub(crate) static TEST_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// What the SDK does when an internal operation fails unexpectedly
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PanicPolicy {
+    /// Panic with the error (default in debug builds)
+    Panic,
+
+    /// Report the error to the error callback and degrade gracefully
+    /// (default in release builds)
+    Degrade,
+}
+
+impl Default for PanicPolicy {
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            PanicPolicy::Panic
+        } else {
+            PanicPolicy::Degrade
+        }
+    }
+}
+
+/// Change the panic policy for the whole process
+pub fn set_panic_policy(policy: PanicPolicy) {
+    let value = match policy {
+        PanicPolicy::Panic => 1,
+        PanicPolicy::Degrade => 2,
+    };
+    PANIC_POLICY.store(value, Ordering::Relaxed);
+}
+
+/// Get the current panic policy
+pub fn panic_policy() -> PanicPolicy {
+    match PANIC_POLICY.load(Ordering::Relaxed) {
+        1 => PanicPolicy::Panic,
+        2 => PanicPolicy::Degrade,
+        _ => PanicPolicy::default(),
+    }
+}
+
+/// Receive errors the SDK can't return to the caller
+///
+/// Called on whichever thread hit the error, including the auto-flush
+/// worker. Panics inside the callback are caught.
+pub fn set_error_callback(callback: impl Fn(&GameEventsIOError) + Send + Sync + 'static) {
+    *ERROR_CALLBACK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(callback));
+}
+
+/// Remove the error callback
+pub fn clear_error_callback() {
+    *ERROR_CALLBACK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// Pass an error to the error callback, if one is set
+pub(crate) fn report(error: &GameEventsIOError) {
+    let callback = ERROR_CALLBACK
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    if let Some(callback) = callback {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| callback(error)));
+    }
+}
+
+/// Handle an internal failure according to the panic policy
+///
+/// Returns `fallback()` when the policy is `Degrade`.
+pub(crate) fn recover<T, E: fmt::Display>(
+    result: Result<T, E>,
+    context: &str,
+    fallback: impl FnOnce() -> T,
+) -> T {
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            let error = GameEventsIOError::Internal(format!("{}: {}", context, e));
+            if panic_policy() == PanicPolicy::Panic {
+                panic!("{}", error);
+            }
+            report(&error);
+            fallback()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_recover_reports_and_falls_back() {
+        let _globals = crate::lock(&TEST_LOCK);
+        static SEEN: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        set_error_callback(|error| SEEN.lock().unwrap().push(error.to_string()));
+        set_panic_policy(PanicPolicy::Degrade);
+
+        let value = recover(Err::<u64, _>("clock unavailable"), "reading time", || 7);
+        assert_eq!(value, 7);
+        assert!(SEEN
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|seen| seen.contains("reading time: clock unavailable")));
+
+        // Panicking callbacks don't escape
+        set_error_callback(|_| panic!("callback bug"));
+        assert_eq!(recover(Err::<u64, _>("again"), "test", || 1), 1);
+
+        clear_error_callback();
+        set_panic_policy(PanicPolicy::default());
+    }
+}
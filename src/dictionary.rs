@@ -0,0 +1,174 @@
+//! Dictionary encoding of batches.
+//!
+//! Property values like item ids or level names repeat across most events of
+//! a batch. In the dictionary envelope each string property value used more
+//! than once is sent a single time, and events reference it by index:
+//!
+//! ```json
+//! {
+//!   "dictionary": ["gem_bundle"],
+//!   "events": [{"event": "offer_shown", "event_properties": {"offer_id": {"$d": 0}}, ...}]
+//! }
+//! ```
+//!
+//! Only top-level values of `event_properties` and `user_properties` are
+//! replaced. Objects of the form `{"$d": n}` are reserved for references.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use crate::{GameEventsIOEvent, WirePayloadV1, WireVersion};
+
+/// Content type of dictionary-encoded uploads
+pub const DICTIONARY_CONTENT_TYPE: &str = "application/vnd.game-events.dictionary+json";
+
+/// Key of a dictionary reference object
+pub const REFERENCE_KEY: &str = "$d";
+
+/// Shorter strings are not worth a reference
+const MIN_ENTRY_LEN: usize = 4;
+
+const PROPERTY_FIELDS: [&str; 2] = ["event_properties", "user_properties"];
+
+/// Encode a batch into the dictionary envelope
+pub fn encode_batch(batch: &[GameEventsIOEvent]) -> serde_json::Result<Value> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for event in batch {
+        for value in event
+            .event_properties
+            .values()
+            .chain(event.user_properties.values())
+        {
+            if let Some(text) = value.as_str().filter(|text| text.len() >= MIN_ENTRY_LEN) {
+                *counts.entry(text).or_default() += 1;
+            }
+        }
+    }
+
+    // Most used strings first, ties broken by value to keep payloads stable
+    let mut dictionary: Vec<(&str, usize)> =
+        counts.into_iter().filter(|(_, count)| *count > 1).collect();
+    dictionary.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let index: HashMap<&str, usize> = dictionary
+        .iter()
+        .enumerate()
+        .map(|(index, (text, _))| (*text, index))
+        .collect();
+
+    let events = batch
+        .iter()
+        .map(|event| {
+            let mut value = WireVersion::V1.to_value(event)?;
+            for field in PROPERTY_FIELDS {
+                if let Some(Value::Object(properties)) = value.get_mut(field) {
+                    for property in properties.values_mut() {
+                        let position = property.as_str().and_then(|text| index.get(text));
+                        if let Some(&position) = position {
+                            *property = json!({ REFERENCE_KEY: position });
+                        }
+                    }
+                }
+            }
+            Ok(value)
+        })
+        .collect::<serde_json::Result<Vec<_>>>()?;
+
+    let dictionary: Vec<&str> = dictionary.into_iter().map(|(text, _)| text).collect();
+    Ok(json!({
+        "dictionary": dictionary,
+        "events": events,
+    }))
+}
+
+/// Decode a dictionary envelope back into events
+pub fn decode_batch(envelope: &Value) -> serde_json::Result<Vec<GameEventsIOEvent>> {
+    let dictionary: Vec<String> = serde_json::from_value(envelope["dictionary"].clone())?;
+    let mut events: Vec<Value> = serde_json::from_value(envelope["events"].clone())?;
+
+    for event in &mut events {
+        for field in PROPERTY_FIELDS {
+            if let Some(Value::Object(properties)) = event.get_mut(field) {
+                for property in properties.values_mut() {
+                    let Some(position) = reference(property) else {
+                        continue;
+                    };
+                    let text = dictionary.get(position).ok_or_else(|| {
+                        <serde_json::Error as serde::de::Error>::custom(format!(
+                            "unknown dictionary entry {}",
+                            position
+                        ))
+                    })?;
+                    *property = Value::String(text.clone());
+                }
+            }
+        }
+    }
+
+    events
+        .into_iter()
+        .map(|event| serde_json::from_value(event).map(WirePayloadV1::into_event))
+        .collect()
+}
+
+/// Index of a `{"$d": n}` reference
+fn reference(value: &Value) -> Option<usize> {
+    let object = value.as_object().filter(|object| object.len() == 1)?;
+    object
+        .get(REFERENCE_KEY)?
+        .as_u64()
+        .map(|index| index as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameEventsIOEventBuilder;
+
+    #[test]
+    fn test_dictionary_round_trip() {
+        let batch: Vec<GameEventsIOEvent> = (0..50)
+            .map(|i| {
+                GameEventsIOEventBuilder::default()
+                    .event("item_used")
+                    .user_id("user123")
+                    .session_id("session456")
+                    .time(1_700_000_000u64)
+                    .event_properties(HashMap::from([
+                        ("item_id".to_string(), json!("sword_of_a_thousand_truths")),
+                        ("slot".to_string(), json!(i)),
+                        ("unique".to_string(), json!(format!("value-{}", i))),
+                    ]))
+                    .user_properties(HashMap::from([(
+                        "platform".to_string(),
+                        json!("steam_deck"),
+                    )]))
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+
+        let envelope = encode_batch(&batch).unwrap();
+        assert_eq!(
+            envelope["dictionary"],
+            json!(["steam_deck", "sword_of_a_thousand_truths"])
+        );
+        assert_eq!(
+            envelope["events"][0]["event_properties"]["item_id"],
+            json!({ "$d": 1 })
+        );
+        assert_eq!(
+            envelope["events"][0]["event_properties"]["unique"],
+            "value-0"
+        );
+
+        let plain = serde_json::to_vec(&batch).unwrap();
+        let encoded = serde_json::to_vec(&envelope).unwrap();
+        assert!(encoded.len() < plain.len());
+        let decoded = decode_batch(&envelope).unwrap();
+        assert_eq!(
+            serde_json::to_value(decoded).unwrap(),
+            serde_json::to_value(&batch).unwrap()
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Monetization context supplied by the game.
+//!
+//! The game implements `EntitlementProvider`; the session queries it at
+//! session start and on demand, and attaches the result to the user
+//! properties of every event.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// User property holding the current subscription tier
+pub const SUBSCRIPTION_TIER_PROPERTY: &str = "subscription_tier";
+
+/// User property holding the list of owned DLC
+pub const OWNED_DLC_PROPERTY: &str = "owned_dlc";
+
+/// Snapshot of what the player is entitled to
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Entitlements {
+    /// Current subscription tier, if the player is subscribed
+    pub subscription_tier: Option<String>,
+
+    /// Identifiers of the DLC the player owns
+    pub owned_dlc: Vec<String>,
+}
+
+impl Entitlements {
+    /// Add the standardized entitlement properties to a user property map
+    pub fn apply(&self, user_properties: &mut HashMap<String, serde_json::Value>) {
+        match &self.subscription_tier {
+            Some(tier) => {
+                user_properties.insert(
+                    SUBSCRIPTION_TIER_PROPERTY.to_string(),
+                    serde_json::json!(tier),
+                );
+            }
+            None => {
+                user_properties.remove(SUBSCRIPTION_TIER_PROPERTY);
+            }
+        }
+        user_properties.insert(
+            OWNED_DLC_PROPERTY.to_string(),
+            serde_json::json!(self.owned_dlc),
+        );
+    }
+}
+
+/// Hook the game implements to report the player's entitlements
+pub trait EntitlementProvider: Send + Sync {
+    /// Query the player's current entitlements
+    fn entitlements(&self) -> Entitlements;
+}
+
+impl<F> EntitlementProvider for F
+where
+    F: Fn() -> Entitlements + Send + Sync,
+{
+    fn entitlements(&self) -> Entitlements {
+        self()
+    }
+}
+
+impl fmt::Debug for dyn EntitlementProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EntitlementProvider")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameEventsIOSession;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_entitlements_attached_to_events() {
+        let mut session = GameEventsIOSession::new("user123", "session456");
+        session.set_entitlement_provider(|| Entitlements {
+            subscription_tier: Some("gold".to_string()),
+            owned_dlc: vec!["frozen_north".to_string()],
+        });
+        session.push_event("level_started", HashMap::new());
+
+        let events = session.take_events(10);
+        let props = &events[1].user_properties;
+        assert_eq!(props.get(SUBSCRIPTION_TIER_PROPERTY).unwrap(), "gold");
+        assert_eq!(
+            props.get(OWNED_DLC_PROPERTY).unwrap(),
+            &serde_json::json!(["frozen_north"])
+        );
+    }
+
+    #[test]
+    fn test_entitlements_queried_on_demand_only() {
+        let queries = Arc::new(AtomicUsize::new(0));
+        let counter = queries.clone();
+
+        let mut session = GameEventsIOSession::new("user123", "session456");
+        session.set_entitlement_provider(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Entitlements::default()
+        });
+        session.push_event("a", HashMap::new());
+        session.push_event("b", HashMap::new());
+        assert_eq!(queries.load(Ordering::SeqCst), 1);
+
+        session.refresh_entitlements();
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+
+        // A new session queries the provider again
+        session.rotate_session();
+        session.push_event("c", HashMap::new());
+        assert_eq!(queries.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,134 @@
+//! Errors returned by the SDK.
+
+use std::fmt;
+use std::io;
+use std::time::Duration;
+
+use crate::{RetryPolicy, ValidationError};
+
+/// Error returned when sending events fails
+#[derive(Debug)]
+pub enum GameEventsIOError {
+    /// HTTP request failed before a response was received
+    Network(reqwest::Error),
+
+    /// Reading or writing local files failed
+    Io(io::Error),
+
+    /// A custom transport failed
+    Transport(String),
+
+    /// The backend rejected the API key (401 or 403)
+    Unauthorized,
+
+    /// The backend asked to slow down (429)
+    RateLimited {
+        /// How long to wait before sending again, if the backend said
+        retry_after: Option<Duration>,
+    },
+
+    /// The backend refused the batch as too large (413)
+    PayloadTooLarge,
+
+    /// The backend rejected some events of the batch as invalid (422)
+    InvalidEvents {
+        /// Positions of the rejected events in the batch
+        rejected_indices: Vec<usize>,
+    },
+
+    /// The backend answered with any other error status
+    Server {
+        /// HTTP status code
+        status: u16,
+
+        /// Response body
+        body: String,
+    },
+
+    /// An internal operation failed unexpectedly (see `PanicPolicy`)
+    Internal(String),
+
+    /// The event failed validation and was not logged
+    Validation(Vec<ValidationError>),
+}
+
+impl GameEventsIOError {
+    /// Whether sending the same batch again later may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            GameEventsIOError::Network(_)
+            | GameEventsIOError::Io(_)
+            | GameEventsIOError::Transport(_)
+            | GameEventsIOError::RateLimited { .. } => true,
+            GameEventsIOError::Server { status, .. } => RetryPolicy::is_retryable_status(*status),
+            GameEventsIOError::Unauthorized
+            | GameEventsIOError::PayloadTooLarge
+            | GameEventsIOError::InvalidEvents { .. }
+            | GameEventsIOError::Internal(_)
+            | GameEventsIOError::Validation(_) => false,
+        }
+    }
+
+    /// How long the backend asked to wait before retrying, if it said
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            GameEventsIOError::RateLimited { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GameEventsIOError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameEventsIOError::Network(e) => write!(f, "network error: {}", e),
+            GameEventsIOError::Io(e) => write!(f, "I/O error: {}", e),
+            GameEventsIOError::Transport(message) => write!(f, "transport error: {}", message),
+            GameEventsIOError::Unauthorized => write!(f, "invalid or revoked API key"),
+            GameEventsIOError::RateLimited {
+                retry_after: Some(retry_after),
+            } => write!(f, "rate limited, retry after {}s", retry_after.as_secs()),
+            GameEventsIOError::RateLimited { retry_after: None } => write!(f, "rate limited"),
+            GameEventsIOError::PayloadTooLarge => write!(f, "batch too large"),
+            GameEventsIOError::InvalidEvents { rejected_indices } => {
+                write!(f, "{} invalid events rejected", rejected_indices.len())
+            }
+            GameEventsIOError::Server { status, body } => {
+                write!(f, "server error {}: {}", status, body)
+            }
+            GameEventsIOError::Internal(message) => write!(f, "internal error: {}", message),
+            GameEventsIOError::Validation(errors) => {
+                write!(f, "invalid event: ")?;
+                for (i, error) in errors.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for GameEventsIOError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GameEventsIOError::Network(e) => Some(e),
+            GameEventsIOError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for GameEventsIOError {
+    fn from(e: reqwest::Error) -> Self {
+        GameEventsIOError::Network(e)
+    }
+}
+
+impl From<io::Error> for GameEventsIOError {
+    fn from(e: io::Error) -> Self {
+        GameEventsIOError::Io(e)
+    }
+}
@@ -0,0 +1,208 @@
+//! Offline bulk export of event batches.
+//!
+//! Instead of posting to the API, `BulkExportTransport` writes each batch
+//! as a gzip-compressed NDJSON file, either into a local directory or (with
+//! the `s3` feature) into an S3-compatible bucket, for rigs that upload
+//! telemetry out-of-band.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::transport::{Transport, TransportResponse};
+use crate::{unix_now, GameEventsIOError, GameEventsIOEvent, WireVersion};
+
+/// File extension of exported batches
+pub const EXPORT_EXTENSION: &str = "ndjson.gz";
+
+/// Connection settings for an S3-compatible bucket
+#[cfg(feature = "s3")]
+#[derive(Clone, Debug)]
+pub struct S3Config {
+    /// Bucket name
+    pub bucket: String,
+
+    /// Region name (e.g. "us-east-1")
+    pub region: String,
+
+    /// Custom endpoint for S3-compatible stores (MinIO, R2, ...)
+    pub endpoint: Option<String>,
+
+    /// Access key id
+    pub access_key: String,
+
+    /// Secret access key
+    pub secret_key: String,
+
+    /// Use path-style instead of virtual-hosted-style URLs
+    pub path_style: bool,
+}
+
+#[derive(Debug)]
+enum ExportSink {
+    Directory(PathBuf),
+    #[cfg(feature = "s3")]
+    S3(Box<s3::Bucket>),
+}
+
+/// Transport writing compressed batch files instead of posting to the API
+#[derive(Debug)]
+pub struct BulkExportTransport {
+    sink: ExportSink,
+    prefix: String,
+}
+
+impl BulkExportTransport {
+    /// Write batch files into a local directory
+    pub fn to_directory(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            sink: ExportSink::Directory(dir.into()),
+            prefix: String::new(),
+        }
+    }
+
+    /// Upload batch files into an S3-compatible bucket
+    #[cfg(feature = "s3")]
+    pub fn to_s3(config: S3Config) -> Result<Self, GameEventsIOError> {
+        let region = match config.endpoint {
+            Some(endpoint) => s3::Region::Custom {
+                region: config.region,
+                endpoint,
+            },
+            None => config
+                .region
+                .parse()
+                .map_err(|e| GameEventsIOError::Transport(format!("invalid region: {}", e)))?,
+        };
+        let credentials = s3::creds::Credentials::new(
+            Some(&config.access_key),
+            Some(&config.secret_key),
+            None,
+            None,
+            None,
+        )
+        .map_err(|e| GameEventsIOError::Transport(e.to_string()))?;
+
+        let mut bucket = s3::Bucket::new(&config.bucket, region, credentials)
+            .map_err(|e| GameEventsIOError::Transport(e.to_string()))?;
+        if config.path_style {
+            bucket = bucket.with_path_style();
+        }
+
+        Ok(Self {
+            sink: ExportSink::S3(Box::new(bucket)),
+            prefix: String::new(),
+        })
+    }
+
+    /// Prefix prepended to every file name or object key (e.g. "rig-07/")
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    fn next_name(&self) -> String {
+        format!(
+            "{}{}-{}.{}",
+            self.prefix,
+            unix_now(),
+            uuid::Uuid::new_v4().simple(),
+            EXPORT_EXTENSION
+        )
+    }
+}
+
+/// Encode a batch as gzip-compressed NDJSON
+pub fn encode_ndjson_gz(batch: &[GameEventsIOEvent]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for event in batch {
+        serde_json::to_writer(&mut encoder, &WireVersion::V1.to_value(event)?)?;
+        encoder.write_all(b"\n")?;
+    }
+    encoder.finish()
+}
+
+impl Transport for BulkExportTransport {
+    fn send(&self, batch: &[GameEventsIOEvent]) -> Result<TransportResponse, GameEventsIOError> {
+        let contents = encode_ndjson_gz(batch)?;
+        let name = self.next_name();
+
+        match &self.sink {
+            ExportSink::Directory(dir) => {
+                let path = dir.join(&name);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+
+                // Write under a temporary name so collectors never pick up partial files
+                let partial = path.with_extension("part");
+                fs::write(&partial, &contents)?;
+                fs::rename(&partial, &path)?;
+
+                Ok(TransportResponse {
+                    status: 200,
+                    body: path.display().to_string(),
+                    retry_after: None,
+                    server_time: None,
+                })
+            }
+            #[cfg(feature = "s3")]
+            ExportSink::S3(bucket) => {
+                let response = bucket
+                    .put_object_with_content_type(&name, &contents, "application/gzip")
+                    .map_err(|e| GameEventsIOError::Transport(e.to_string()))?;
+
+                Ok(TransportResponse {
+                    status: response.status_code(),
+                    body: name,
+                    retry_after: None,
+                    server_time: None,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameEventsIOEventBuilder;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_directory_export() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", uuid::Uuid::new_v4()));
+        let transport = BulkExportTransport::to_directory(&dir).prefix("rig-01/");
+
+        let batch: Vec<GameEventsIOEvent> = (0..3)
+            .map(|i| {
+                GameEventsIOEventBuilder::default()
+                    .event(format!("soak_tick_{}", i))
+                    .user_id("rig")
+                    .session_id("soak")
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+
+        let response = transport.send(&batch).unwrap();
+        let path = PathBuf::from(response.body);
+        assert!(path.starts_with(dir.join("rig-01")));
+        assert!(path.to_string_lossy().ends_with(EXPORT_EXTENSION));
+
+        let mut contents = String::new();
+        GzDecoder::new(fs::File::open(&path).unwrap())
+            .read_to_string(&mut contents)
+            .unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let first: GameEventsIOEvent = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first.event, "soak_tick_0");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
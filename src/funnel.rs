@@ -0,0 +1,131 @@
+//! Ordered funnels.
+//!
+//! A `Funnel` is a named list of steps the player is expected to complete
+//! in order (e.g. tutorial, first match, first purchase). Each step
+//! completed in order pushes a `funnel_step` event with the step and its
+//! index, so drop-off between steps can be charted without joining
+//! unrelated events. Steps completed out of order or twice are ignored.
+
+use std::collections::HashMap;
+
+use serde_json::json;
+
+use crate::GameEventsIOSession;
+
+/// Event pushed when a funnel step is completed
+pub const EVENT_FUNNEL_STEP: &str = "funnel_step";
+
+/// Property keys of `funnel_step`
+pub mod keys {
+    /// Funnel name
+    pub const FUNNEL: &str = "funnel";
+    /// Name of the completed step
+    pub const STEP: &str = "step";
+    /// 1-based position of the step in the funnel
+    pub const STEP_INDEX: &str = "step_index";
+    /// Number of steps in the funnel
+    pub const STEP_COUNT: &str = "step_count";
+}
+
+/// Tracks the completion of ordered steps
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Funnel {
+    name: String,
+    steps: Vec<String>,
+
+    /// Index of the next step expected
+    next: usize,
+}
+
+impl Funnel {
+    /// Create a funnel with its steps, in the order they should be completed
+    pub fn new<S: Into<String>>(
+        name: impl Into<String>,
+        steps: impl IntoIterator<Item = S>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            steps: steps.into_iter().map(Into::into).collect(),
+            next: 0,
+        }
+    }
+
+    /// Funnel name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Mark a step as completed, pushing its `funnel_step` event
+    ///
+    /// Returns `false`, without pushing anything, unless `step` is the next
+    /// step of the funnel.
+    pub fn complete(&mut self, session: &GameEventsIOSession, step: &str) -> bool {
+        if self.steps.get(self.next).map(String::as_str) != Some(step) {
+            return false;
+        }
+
+        self.next += 1;
+        session.push_event(
+            EVENT_FUNNEL_STEP,
+            HashMap::from([
+                (keys::FUNNEL.to_string(), json!(self.name)),
+                (keys::STEP.to_string(), json!(step)),
+                (keys::STEP_INDEX.to_string(), json!(self.next)),
+                (keys::STEP_COUNT.to_string(), json!(self.steps.len())),
+            ]),
+        );
+        true
+    }
+
+    /// Number of steps completed so far
+    pub fn completed_steps(&self) -> usize {
+        self.next
+    }
+
+    /// Next step expected, `None` once the funnel is complete
+    pub fn next_step(&self) -> Option<&str> {
+        self.steps.get(self.next).map(String::as_str)
+    }
+
+    /// Whether every step was completed
+    pub fn is_complete(&self) -> bool {
+        self.next == self.steps.len()
+    }
+
+    /// Start over from the first step
+    pub fn reset(&mut self) {
+        self.next = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_funnel_steps_in_order() {
+        let session = GameEventsIOSession::new("user123", "session456");
+        let mut funnel = Funnel::new("onboarding", ["tutorial", "first_match", "first_purchase"]);
+
+        assert!(!funnel.complete(&session, "first_match"));
+        assert!(funnel.complete(&session, "tutorial"));
+        assert!(!funnel.complete(&session, "tutorial"));
+        assert!(funnel.complete(&session, "first_match"));
+        assert_eq!(funnel.next_step(), Some("first_purchase"));
+        assert!(funnel.complete(&session, "first_purchase"));
+        assert!(funnel.is_complete());
+
+        let steps: Vec<_> = session
+            .take_events(10)
+            .into_iter()
+            .filter(|event| event.event == EVENT_FUNNEL_STEP)
+            .collect();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[1].event_properties[keys::STEP], "first_match");
+        assert_eq!(steps[1].event_properties[keys::STEP_INDEX], 2);
+        assert_eq!(steps[2].event_properties[keys::STEP_COUNT], 3);
+
+        funnel.reset();
+        assert_eq!(funnel.completed_steps(), 0);
+    }
+}
@@ -0,0 +1,210 @@
+//! Godot integration through gdext (feature `godot`).
+//!
+//! `GameEventsIONode` wraps a client and a session in a Godot node, so
+//! GDScript gets the same semantics as Rust code: events go through the
+//! session (new-session events, user properties) and then the client
+//! (interceptors, sampling, validation, offline queue). Add the node as an
+//! autoload to use it as a singleton:
+//!
+//! ```gdscript
+//! GameEvents.track_event("level_started", {"level": 3})
+//! GameEvents.set_user_property("difficulty", "hard")
+//! GameEvents.flush()
+//! ```
+//!
+//! The client is created in `_ready` from the exported `api_key` and
+//! `user_id`, and unsent events are kept in `queue_path` between runs. On
+//! leaving the tree, the node sends what it can within `SHUTDOWN_TIMEOUT`.
+
+// gdext's `GodotClass`/`godot_api` expansions return its large `CallError`
+#![allow(clippy::result_large_err)]
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use ::godot::classes::{INode, Node, ProjectSettings};
+use ::godot::prelude::*;
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use crate::{diagnostics, GameEventsIOClient, GameEventsIOClientBuilder, GameEventsIOSession};
+
+/// Time the node spends sending events when it leaves the tree
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Default location of the offline queue, inside the Godot user directory
+pub const DEFAULT_QUEUE_PATH: &str = "user://game_events_queue.jsonl";
+
+/// Node exposing the SDK to GDScript
+#[derive(GodotClass)]
+#[class(base = Node)]
+pub struct GameEventsIONode {
+    /// API key for authentication
+    #[export]
+    api_key: GString,
+
+    /// Player identifier; a random one is used if empty
+    #[export]
+    user_id: GString,
+
+    /// Offline queue file (`user://` paths are supported)
+    #[export]
+    queue_path: GString,
+
+    client: Option<GameEventsIOClient>,
+    session: Option<GameEventsIOSession>,
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl INode for GameEventsIONode {
+    fn init(base: Base<Node>) -> Self {
+        Self {
+            api_key: GString::new(),
+            user_id: GString::new(),
+            queue_path: GString::from(DEFAULT_QUEUE_PATH),
+            client: None,
+            session: None,
+            base,
+        }
+    }
+
+    fn ready(&mut self) {
+        let queue_path = ProjectSettings::singleton()
+            .globalize_path(&self.queue_path)
+            .to_string();
+        self.client = Some(diagnostics::recover(
+            GameEventsIOClientBuilder::default()
+                .api_key(self.api_key.to_string())
+                .persist_to(queue_path)
+                .build(),
+            "Failed to create GameEventsIOClient",
+            || GameEventsIOClient::new(self.api_key.to_string()),
+        ));
+
+        let user_id = match self.user_id.to_string() {
+            id if id.is_empty() => Uuid::new_v4().to_string(),
+            id => id,
+        };
+        self.session = Some(GameEventsIOSession::new(
+            user_id,
+            Uuid::new_v4().to_string(),
+        ));
+        self.collect();
+    }
+
+    fn exit_tree(&mut self) {
+        self.collect();
+        if let Some(client) = self.client.as_mut() {
+            if let Err(e) = client.shutdown(SHUTDOWN_TIMEOUT) {
+                diagnostics::report(&e);
+            }
+        }
+    }
+}
+
+#[godot_api]
+impl GameEventsIONode {
+    /// Log an event with a dictionary of properties
+    #[func]
+    fn track_event(&mut self, name: GString, properties: Dictionary) {
+        let Some(session) = self.session.as_ref() else {
+            godot_warn!("GameEventsIONode: track_event called before _ready");
+            return;
+        };
+        session.push_event(name.to_string(), dictionary_to_properties(&properties));
+        self.collect();
+    }
+
+    /// Add or update a user property sent with every following event
+    #[func]
+    fn set_user_property(&mut self, key: GString, value: Variant) {
+        if let Some(session) = self.session.as_ref() {
+            session.set_user_property(key.to_string(), to_json(&value));
+        }
+    }
+
+    /// Send all buffered events, returning whether the upload succeeded
+    #[func]
+    fn flush(&mut self) -> bool {
+        self.collect();
+        let Some(client) = self.client.as_mut() else {
+            return false;
+        };
+        match client.flush() {
+            Ok(_) => true,
+            Err(e) => {
+                diagnostics::report(&e);
+                false
+            }
+        }
+    }
+
+    /// Number of events not sent yet
+    #[func]
+    fn pending_events_count(&self) -> i64 {
+        let session = self
+            .session
+            .as_ref()
+            .map_or(0, GameEventsIOSession::pending_events_count);
+        let client = self
+            .client
+            .as_ref()
+            .map_or(0, GameEventsIOClient::pending_events_count);
+        (session + client) as i64
+    }
+
+    /// Opt the player out of (`true`) or back in to (`false`) tracking
+    #[func]
+    fn set_opt_out(&mut self, opted_out: bool) {
+        if let Some(client) = self.client.as_mut() {
+            if let Err(e) = client.set_opt_out(opted_out) {
+                diagnostics::report(&e.into());
+            }
+        }
+    }
+}
+
+impl GameEventsIONode {
+    /// Move the session's events into the client
+    fn collect(&mut self) {
+        if let (Some(session), Some(client)) = (self.session.as_ref(), self.client.as_mut()) {
+            for event in session.take_events(usize::MAX) {
+                client.log_event(event);
+            }
+        }
+    }
+}
+
+/// JSON value of a GDScript value; unsupported types become their string form
+fn to_json(value: &Variant) -> Value {
+    match value.get_type() {
+        VariantType::NIL => Value::Null,
+        VariantType::BOOL => json!(value.to::<bool>()),
+        VariantType::INT => json!(value.to::<i64>()),
+        VariantType::FLOAT => json!(value.to::<f64>()),
+        VariantType::ARRAY => Value::Array(
+            value
+                .to::<VariantArray>()
+                .iter_shared()
+                .map(|item| to_json(&item))
+                .collect(),
+        ),
+        VariantType::DICTIONARY => Value::Object(
+            value
+                .to::<Dictionary>()
+                .iter_shared()
+                .map(|(key, item)| (key.to_string(), to_json(&item)))
+                .collect::<Map<_, _>>(),
+        ),
+        _ => Value::String(value.to_string()),
+    }
+}
+
+/// Properties of a GDScript dictionary
+pub fn dictionary_to_properties(dictionary: &Dictionary) -> HashMap<String, Value> {
+    dictionary
+        .iter_shared()
+        .map(|(key, value)| (key.to_string(), to_json(&value)))
+        .collect()
+}
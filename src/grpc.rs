@@ -0,0 +1,242 @@
+//! gRPC transport for self-hosted ingest collectors.
+//!
+//! Speaks the `gameevents.ingest.v1.Ingest` service defined in
+//! `proto/ingest.proto`. Each flush is streamed to the collector as
+//! several `EventBatch` messages over a single client-streaming call.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tonic::codec::ProstCodec;
+use tonic::metadata::AsciiMetadataValue;
+use tonic::transport::Endpoint;
+use tonic::Code;
+
+use crate::transport::{Transport, TransportResponse};
+use crate::{GameEventsIOError, GameEventsIOEvent, PurchaseReceipt, Store};
+
+const UPLOAD_PATH: &str = "/gameevents.ingest.v1.Ingest/Upload";
+
+/// Protobuf messages of the ingest service, generated from `proto/ingest.proto`
+pub mod proto {
+    tonic::include_proto!("gameevents.ingest.v1");
+}
+
+fn encode_properties(properties: &HashMap<String, serde_json::Value>) -> HashMap<String, String> {
+    properties
+        .iter()
+        .map(|(key, value)| (key.clone(), value.to_string()))
+        .collect()
+}
+
+impl From<&GameEventsIOEvent> for proto::Event {
+    fn from(event: &GameEventsIOEvent) -> Self {
+        proto::Event {
+            event: event.event.clone(),
+            user_id: event.user_id.clone(),
+            session_id: event.session_id.clone(),
+            time: event.time,
+            event_properties: encode_properties(&event.event_properties),
+            user_properties: encode_properties(&event.user_properties),
+            client_upload_time: event.client_upload_time,
+            extra: event
+                .extra
+                .iter()
+                .map(|(key, value)| (key.clone(), value.to_string()))
+                .collect(),
+            time_ms: event.time_ms,
+            seq: event.seq,
+            client_upload_time_ms: event.client_upload_time_ms,
+            tags: event.tags.clone(),
+            receipt: event.receipt.as_ref().map(proto::PurchaseReceipt::from),
+        }
+    }
+}
+
+impl From<&PurchaseReceipt> for proto::PurchaseReceipt {
+    fn from(receipt: &PurchaseReceipt) -> Self {
+        let store = match receipt.store {
+            Store::AppStore => proto::Store::AppStore,
+            Store::GooglePlay => proto::Store::GooglePlay,
+            Store::Steam => proto::Store::Steam,
+        };
+        proto::PurchaseReceipt {
+            store: store as i32,
+            receipt: receipt.receipt.clone(),
+            signature: receipt.signature.clone(),
+        }
+    }
+}
+
+/// Closest HTTP status for a gRPC status code
+fn http_status(code: Code) -> u16 {
+    match code {
+        Code::Ok => 200,
+        Code::InvalidArgument | Code::FailedPrecondition | Code::OutOfRange => 422,
+        Code::Unauthenticated => 401,
+        Code::PermissionDenied => 403,
+        Code::NotFound => 404,
+        Code::ResourceExhausted => 429,
+        Code::Unavailable => 503,
+        Code::DeadlineExceeded => 504,
+        _ => 500,
+    }
+}
+
+/// Transport streaming batches to a gRPC ingest collector
+///
+/// Owns a single-threaded tokio runtime, so `send` must not be called from
+/// inside another async runtime.
+#[derive(Debug)]
+pub struct GrpcTransport {
+    endpoint: Endpoint,
+    api_key: String,
+    events_per_message: usize,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl GrpcTransport {
+    /// Create a transport for the collector at `url` (e.g. `http://collector:50051`)
+    pub fn new(
+        url: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Result<Self, GameEventsIOError> {
+        let endpoint = Endpoint::from_shared(url.into())
+            .map_err(|e| GameEventsIOError::Transport(e.to_string()))?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        Ok(Self {
+            endpoint,
+            api_key: api_key.into(),
+            events_per_message: 500,
+            runtime,
+        })
+    }
+
+    /// Set how many events go into each streamed message (default 500)
+    pub fn events_per_message(mut self, events_per_message: usize) -> Self {
+        self.events_per_message = events_per_message.max(1);
+        self
+    }
+
+    async fn upload(
+        &self,
+        batch: &[GameEventsIOEvent],
+    ) -> Result<TransportResponse, GameEventsIOError> {
+        let channel = self
+            .endpoint
+            .connect()
+            .await
+            .map_err(|e| GameEventsIOError::Transport(e.to_string()))?;
+        let mut grpc = tonic::client::Grpc::new(channel);
+        grpc.ready()
+            .await
+            .map_err(|e| GameEventsIOError::Transport(e.to_string()))?;
+
+        let messages: Vec<proto::EventBatch> = batch
+            .chunks(self.events_per_message)
+            .map(|chunk| proto::EventBatch {
+                events: chunk.iter().map(proto::Event::from).collect(),
+            })
+            .collect();
+
+        let mut request = tonic::Request::new(tokio_stream::iter(messages));
+        let authorization = format!("Bearer {}", self.api_key)
+            .parse::<AsciiMetadataValue>()
+            .map_err(|e| GameEventsIOError::Transport(e.to_string()))?;
+        request
+            .metadata_mut()
+            .insert("authorization", authorization);
+
+        let codec = ProstCodec::<proto::EventBatch, proto::UploadResponse>::default();
+        let path = http::uri::PathAndQuery::from_static(UPLOAD_PATH);
+        match grpc.client_streaming(request, path, codec).await {
+            Ok(response) => {
+                let reply = response.into_inner();
+                Ok(TransportResponse {
+                    status: 200,
+                    body: serde_json::json!({
+                        "accepted": reply.accepted,
+                        "message": reply.message,
+                    })
+                    .to_string(),
+                    retry_after: None,
+                    server_time: None,
+                })
+            }
+            Err(status) => Ok(TransportResponse {
+                status: http_status(status.code()),
+                body: status.message().to_string(),
+                retry_after: None,
+                server_time: None,
+            }),
+        }
+    }
+}
+
+impl Transport for GrpcTransport {
+    fn send(&self, batch: &[GameEventsIOEvent]) -> Result<TransportResponse, GameEventsIOError> {
+        self.runtime.block_on(self.upload(batch))
+    }
+
+    fn send_within(
+        &self,
+        batch: &[GameEventsIOEvent],
+        timeout: Duration,
+    ) -> Result<TransportResponse, GameEventsIOError> {
+        self.runtime
+            .block_on(tokio::time::timeout(timeout, self.upload(batch)))
+            .unwrap_or_else(|_| Err(GameEventsIOError::Transport("upload timed out".to_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameEventsIOEventBuilder;
+
+    #[test]
+    fn test_event_to_proto() {
+        let mut props = HashMap::new();
+        props.insert("level".to_string(), serde_json::json!(5));
+        props.insert("mode".to_string(), serde_json::json!("hard"));
+
+        let event = GameEventsIOEventBuilder::default()
+            .event("level_completed")
+            .user_id("user123")
+            .session_id("session456")
+            .time(42u64)
+            .time_ms(42_123u64)
+            .seq(7u64)
+            .event_properties(props)
+            .build()
+            .unwrap();
+
+        let message = proto::Event::from(&event);
+        assert_eq!(message.event, "level_completed");
+        assert_eq!(message.time, 42);
+        assert_eq!(message.time_ms, Some(42_123));
+        assert_eq!(message.seq, Some(7));
+        assert_eq!(message.event_properties.get("level").unwrap(), "5");
+        assert_eq!(message.event_properties.get("mode").unwrap(), "\"hard\"");
+
+        let mut purchase = GameEventsIOEvent::purchase("gem_pack", 4.99, "USD")
+            .tag("qa")
+            .build()
+            .unwrap();
+        purchase.receipt = Some(PurchaseReceipt::new(Store::GooglePlay, "{}").signature("c2ln"));
+        let purchase_message = proto::Event::from(&purchase);
+        assert_eq!(purchase_message.tags, ["qa"]);
+        let receipt = purchase_message.receipt.unwrap();
+        assert_eq!(receipt.store, proto::Store::GooglePlay as i32);
+        assert_eq!(receipt.signature.as_deref(), Some("c2ln"));
+    }
+
+    #[test]
+    fn test_status_mapping() {
+        assert_eq!(http_status(Code::Unauthenticated), 401);
+        assert_eq!(http_status(Code::Unavailable), 503);
+    }
+}
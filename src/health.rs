@@ -0,0 +1,167 @@
+//! One-call check that analytics work on this machine.
+//!
+//! `GameEventsIOClient::self_test` sends a single ping event through the
+//! client's transport, bypassing the buffer, and reports whether the
+//! backend was reachable, accepted the API key, answered in the expected
+//! format, and agrees with the local clock.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+use crate::{
+    diagnostics, unix_now, FlushResult, GameEventsIOError, GameEventsIOEvent,
+    GameEventsIOEventBuilder, Transport,
+};
+
+/// Event sent by `self_test`, tagged with `SELF_TEST_TAG`
+pub const EVENT_SELF_TEST: &str = "sdk_self_test";
+
+/// Tag of self-test pings, so the backend can keep them out of reports
+pub const SELF_TEST_TAG: &str = "self_test";
+
+/// Largest clock difference with the backend considered healthy
+pub const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// Outcome of `GameEventsIOClient::self_test`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SelfTestReport {
+    /// The backend answered the ping
+    pub reachable: bool,
+
+    /// The backend accepted the API key
+    pub authorized: bool,
+
+    /// The backend accepted the ping and its answer could be parsed
+    pub accepted: bool,
+
+    /// Backend clock minus local clock in seconds, if the backend reported it
+    pub clock_skew: Option<i64>,
+
+    /// Time the ping took
+    pub round_trip: Duration,
+
+    /// What went wrong, if anything
+    pub error: Option<String>,
+}
+
+impl SelfTestReport {
+    /// Whether the local clock is within `MAX_CLOCK_SKEW` of the backend's
+    ///
+    /// `true` when the backend didn't report its time.
+    pub fn clock_in_sync(&self) -> bool {
+        match self.clock_skew {
+            Some(skew) => skew.unsigned_abs() <= MAX_CLOCK_SKEW.as_secs(),
+            None => true,
+        }
+    }
+
+    /// Whether every check passed
+    pub fn is_healthy(&self) -> bool {
+        self.reachable && self.authorized && self.accepted && self.clock_in_sync()
+    }
+}
+
+/// Send one ping through `transport` and diagnose the answer
+pub(crate) fn run(transport: &dyn Transport) -> SelfTestReport {
+    let ping = ping_event();
+    let started = Instant::now();
+    let response = transport.send(std::slice::from_ref(&ping));
+    let mut report = SelfTestReport {
+        round_trip: started.elapsed(),
+        ..SelfTestReport::default()
+    };
+
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            report.error = Some(e.to_string());
+            return report;
+        }
+    };
+    report.reachable = true;
+    report.clock_skew = response
+        .server_time
+        .map(|server_time| server_time as i64 - ping.time as i64);
+
+    match FlushResult::from_response(response, 1) {
+        Ok(result) => {
+            report.authorized = true;
+            report.accepted = result.accepted == 1;
+            if !report.accepted {
+                report.error = Some("backend rejected the ping event".to_string());
+            }
+        }
+        Err(e) => {
+            report.authorized = !matches!(e, GameEventsIOError::Unauthorized);
+            report.error = Some(e.to_string());
+        }
+    }
+    report
+}
+
+fn ping_event() -> GameEventsIOEvent {
+    let id = Uuid::new_v4().to_string();
+    diagnostics::recover(
+        GameEventsIOEventBuilder::default()
+            .event(EVENT_SELF_TEST)
+            .user_id(id.clone())
+            .session_id(id)
+            .time(unix_now())
+            .event_properties(HashMap::from([(
+                "sdk_version".to_string(),
+                serde_json::json!(env!("CARGO_PKG_VERSION")),
+            )]))
+            .tag(SELF_TEST_TAG)
+            .build(),
+        "Failed to build self-test event",
+        GameEventsIOEvent::default,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameEventsIOClientBuilder, MemoryTransport, TransportResponse};
+    use std::sync::Arc;
+
+    /// Answers with a fixed status and a backend clock ten minutes ahead
+    struct SkewedTransport(u16);
+
+    impl Transport for SkewedTransport {
+        fn send(
+            &self,
+            _batch: &[GameEventsIOEvent],
+        ) -> Result<TransportResponse, GameEventsIOError> {
+            Ok(TransportResponse {
+                status: self.0,
+                server_time: Some(unix_now() + 600),
+                ..TransportResponse::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_self_test_diagnosis() {
+        let transport = Arc::new(MemoryTransport::new());
+        let client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+        let report = client.self_test();
+        assert!(report.is_healthy(), "{:?}", report);
+        assert_eq!(client.pending_events_count(), 0);
+        assert!(transport.events()[0].has_tag(SELF_TEST_TAG));
+
+        let report = run(&SkewedTransport(401));
+        assert!(report.reachable && !report.authorized);
+        assert!(report.clock_skew.unwrap() >= 599);
+        assert!(!report.clock_in_sync());
+
+        let report = run(&SkewedTransport(200));
+        assert!(report.authorized && report.accepted);
+        assert!(!report.is_healthy());
+    }
+}
@@ -0,0 +1,140 @@
+//! Player identity across login.
+//!
+//! A player starts with an anonymous user id, persisted in the storage
+//! directory so it is stable across launches. After login,
+//! `GameEventsIOSession::identify` switches the session to the real id; the
+//! anonymous id is kept in the `anonymous_id` user property of every later
+//! event, so the backend can stitch the histories together. `alias` asks
+//! the backend to merge two ids explicitly.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{storage, write, GameEventsIOSession};
+
+/// Event recorded by `identify`, carrying `PREVIOUS_ID_PROPERTY`
+pub const EVENT_IDENTIFY: &str = "identify";
+
+/// Event recorded by `alias`, carrying `PREVIOUS_ID_PROPERTY`
+pub const EVENT_ALIAS: &str = "alias";
+
+/// Event property with the user id that was replaced
+pub const PREVIOUS_ID_PROPERTY: &str = "previous_id";
+
+/// User property with the id the player had before the first `identify`
+pub const ANONYMOUS_ID_PROPERTY: &str = "anonymous_id";
+
+#[derive(Serialize, Deserialize)]
+struct AnonymousId {
+    anonymous_id: String,
+}
+
+/// Anonymous user id persisted in the storage directory, created on first use
+///
+/// Removed when the player opts out, so opting back in starts a new identity.
+pub fn anonymous_id(storage_dir: impl AsRef<Path>) -> io::Result<String> {
+    let dir = storage_dir.as_ref();
+    if let Some(AnonymousId { anonymous_id }) = storage::ANONYMOUS_ID.load(dir)? {
+        return Ok(anonymous_id);
+    }
+
+    let anonymous_id = Uuid::new_v4().to_string();
+    storage::ANONYMOUS_ID.save(
+        dir,
+        &AnonymousId {
+            anonymous_id: anonymous_id.clone(),
+        },
+    )?;
+    Ok(anonymous_id)
+}
+
+impl GameEventsIOSession {
+    /// Start a session for the anonymous user id persisted in `storage_dir`
+    pub fn anonymous(storage_dir: impl AsRef<Path>) -> io::Result<Self> {
+        let user_id = anonymous_id(storage_dir)?;
+        Ok(Self::new(user_id, Uuid::new_v4().to_string()))
+    }
+
+    /// Switch to the player's real user id (e.g. after login)
+    ///
+    /// Records an `identify` event for the new id with the previous one, and
+    /// keeps the id from before the first `identify` in the `anonymous_id`
+    /// user property. Does nothing if `user_id` is already the current id.
+    pub fn identify(&self, user_id: impl Into<String>) {
+        let user_id = user_id.into();
+        // Micro events pushed so far belong to the previous id
+        self.drain_micro_events(&self.current_session_id());
+        let previous = {
+            let mut current = write(&self.user_id);
+            if user_id == *current {
+                return;
+            }
+            std::mem::replace(&mut *current, user_id)
+        };
+        write(&self.user_properties)
+            .entry(ANONYMOUS_ID_PROPERTY.to_string())
+            .or_insert_with(|| json!(previous.clone()));
+        self.push_event(
+            EVENT_IDENTIFY,
+            HashMap::from([(PREVIOUS_ID_PROPERTY.to_string(), json!(previous))]),
+        );
+    }
+
+    /// Ask the backend to merge the history of `previous_id` into `user_id`
+    ///
+    /// Only records an `alias` event; the session keeps its current user id.
+    pub fn alias(&self, previous_id: impl Into<String>, user_id: impl Into<String>) {
+        self.push_event(
+            EVENT_ALIAS,
+            HashMap::from([
+                (PREVIOUS_ID_PROPERTY.to_string(), json!(previous_id.into())),
+                ("user_id".to_string(), json!(user_id.into())),
+            ]),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_identify_after_login() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", Uuid::new_v4()));
+        let anonymous = anonymous_id(&dir).unwrap();
+        assert_eq!(anonymous_id(&dir).unwrap(), anonymous);
+
+        let session = GameEventsIOSession::anonymous(&dir).unwrap();
+        assert_eq!(session.user_id(), anonymous);
+        session.identify("real_user_42");
+        session.identify("real_user_42");
+        session.push_event("level_started", HashMap::new());
+        session.alias(anonymous.clone(), "real_user_42");
+
+        let events = session.take_events(10);
+        let names: Vec<_> = events.iter().map(|e| e.event.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                crate::EVENT_NEW_SESSION,
+                EVENT_IDENTIFY,
+                "level_started",
+                EVENT_ALIAS
+            ]
+        );
+        assert_eq!(events[0].user_id, anonymous);
+        assert_eq!(events[1].event_properties[PREVIOUS_ID_PROPERTY], anonymous);
+        for event in &events[1..] {
+            assert_eq!(event.user_id, "real_user_42");
+            assert_eq!(event.user_properties[ANONYMOUS_ID_PROPERTY], anonymous);
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
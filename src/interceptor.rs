@@ -0,0 +1,170 @@
+//! Hooks that process events before they are buffered.
+//!
+//! Interceptors run in the order they were added, on every logged event,
+//! before string cleanup and validation. Each one can change the event
+//! (strip PII, add a build hash) or drop it (debug events in release
+//! builds). A panicking interceptor is skipped: its changes are rolled
+//! back, the panic goes to the error callback and the event continues to
+//! the next interceptor, whatever the `PanicPolicy`.
+//!
+//! Without interceptors, logging doesn't copy the event. With some, the
+//! event is copied once as logged; after a panic, it is restored from the
+//! copy and the interceptors that ran before are applied again.
+
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+use crate::{diagnostics, GameEventsIOError, GameEventsIOEvent};
+
+/// Hook run on every event before it is buffered
+pub trait Interceptor: Send + Sync {
+    /// Change the event in place; return `false` to drop it
+    fn intercept(&self, event: &mut GameEventsIOEvent) -> bool;
+}
+
+impl<F> Interceptor for F
+where
+    F: Fn(&mut GameEventsIOEvent) -> bool + Send + Sync,
+{
+    fn intercept(&self, event: &mut GameEventsIOEvent) -> bool {
+        self(event)
+    }
+}
+
+impl fmt::Debug for dyn Interceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Interceptor")
+    }
+}
+
+/// Run the interceptors in order, stopping at the first that drops the event
+///
+/// A panicking interceptor passes the event through unchanged.
+pub(crate) fn run(interceptors: &[Arc<dyn Interceptor>], event: &mut GameEventsIOEvent) -> bool {
+    if interceptors.is_empty() {
+        return true;
+    }
+
+    let logged = event.clone();
+    // Interceptors that panicked on this event, skipped from then on
+    let mut skipped = Vec::new();
+    for (index, interceptor) in interceptors.iter().enumerate() {
+        match intercept(interceptor, event) {
+            Ok(true) => {}
+            Ok(false) => return false,
+            Err(message) => {
+                diagnostics::report(&GameEventsIOError::Internal(message));
+                skipped.push(index);
+                *event = logged.clone();
+                for (index, interceptor) in interceptors[..index].iter().enumerate() {
+                    if skipped.contains(&index) {
+                        continue;
+                    }
+                    // Each of these already kept the event once; if one now
+                    // panics, the half-processed event is dropped
+                    match intercept(interceptor, event) {
+                        Ok(true) => {}
+                        Ok(false) => return false,
+                        Err(message) => {
+                            diagnostics::report(&GameEventsIOError::Internal(message));
+                            return false;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    true
+}
+
+/// Run one interceptor, turning a panic into its message
+fn intercept(
+    interceptor: &Arc<dyn Interceptor>,
+    event: &mut GameEventsIOEvent,
+) -> Result<bool, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| interceptor.intercept(event)))
+        .map_err(|payload| format!("Interceptor panicked: {}", panic_message(payload.as_ref())))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameEventsIOClientBuilder, GameEventsIOEventBuilder};
+
+    #[test]
+    fn test_interceptors_mutate_and_drop() {
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .interceptor(|event: &mut GameEventsIOEvent| !event.event.starts_with("debug_"))
+            .build()
+            .unwrap();
+        client.add_interceptor(|event: &mut GameEventsIOEvent| {
+            event.event_properties.remove("email");
+            event
+                .user_properties
+                .insert("build".to_string(), serde_json::json!("a1b2c3"));
+            true
+        });
+
+        for name in ["debug_overlay", "level_started", "boom"] {
+            let event = GameEventsIOEventBuilder::default()
+                .event(name)
+                .event_properties(std::collections::HashMap::from([(
+                    "email".to_string(),
+                    serde_json::json!("player@example.com"),
+                )]))
+                .build()
+                .unwrap();
+            client.log_event(event);
+        }
+        assert_eq!(client.pending_events_count(), 2);
+        let logged = client.query(crate::EventQuery::name("level_started"));
+        assert!(logged[0].event_properties.is_empty());
+        assert_eq!(logged[0].user_properties["build"], "a1b2c3");
+
+        // A panicking interceptor passes the event through unchanged
+        let _globals = crate::lock(&diagnostics::TEST_LOCK);
+        let reported = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = reported.clone();
+        crate::set_error_callback(move |error| seen.lock().unwrap().push(error.to_string()));
+        client.add_interceptor(|event: &mut GameEventsIOEvent| {
+            event
+                .event_properties
+                .insert("half".to_string(), serde_json::json!(true));
+            assert_ne!(event.event, "boom", "boom interceptor test");
+            true
+        });
+        let event = GameEventsIOEventBuilder::default()
+            .event("boom")
+            .build()
+            .unwrap();
+        client.log_event(event);
+        assert_eq!(client.pending_events_count(), 3);
+        let logged = client.query(crate::EventQuery::name("boom"));
+        assert_eq!(logged.len(), 2);
+        assert!(logged
+            .iter()
+            .all(|event| !event.event_properties.contains_key("half")));
+        // Changes of the interceptors before the panicking one are kept
+        assert_eq!(logged[1].user_properties["build"], "a1b2c3");
+        diagnostics::clear_error_callback();
+        assert!(reported
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|message| message.contains("Interceptor panicked")
+                && message.contains("boom interceptor test")));
+    }
+}
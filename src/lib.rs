@@ -3,14 +3,160 @@
 #[macro_use]
 extern crate derive_builder;
 
+use crossbeam_queue::SegQueue;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{
+    Arc, Mutex, MutexGuard, OnceLock, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::retry::FailedBatch;
+
+#[cfg(feature = "async")]
+pub mod async_client;
+pub mod automation;
+pub mod clock;
+pub mod compat;
+pub mod compression;
+pub mod context;
+pub mod diagnostics;
+pub mod dictionary;
+pub mod entitlements;
+mod error;
+pub mod export;
+pub mod funnel;
+#[cfg(feature = "godot")]
+pub mod godot;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod health;
+pub mod identity;
+pub mod interceptor;
+mod macros;
+pub mod micro;
+pub mod ndjson;
+pub mod network;
+pub mod observer;
+pub mod overflow;
+pub mod perf;
+pub mod power;
+pub mod priority;
+pub mod privacy;
+pub mod purchase;
+pub mod query;
+mod queue;
+pub mod ratelimit;
+#[cfg(feature = "reporting")]
+pub mod reporting;
+pub mod resume;
+pub mod retry;
+pub mod sampling;
+pub mod shared;
+#[cfg(all(feature = "signals", unix))]
+pub mod signals;
+pub mod standard;
+mod storage;
+pub mod storm;
+pub mod summary;
+pub mod text;
+pub mod timed;
+pub mod transport;
+pub mod unique;
+pub mod validation;
+pub mod wire;
+pub mod worker;
+
+#[cfg(feature = "async")]
+pub use async_client::{
+    AsyncGameEventsIOClient, AsyncGameEventsIOClientBuilder, AsyncSharedClient,
+};
+pub use automation::AutomationSuppression;
+pub use compression::{Compression, Compressor};
+pub use context::{ContextProvider, DeviceContext};
+pub use diagnostics::{set_error_callback, set_panic_policy, PanicPolicy};
+pub use entitlements::{EntitlementProvider, Entitlements};
+pub use error::GameEventsIOError;
+pub use export::BulkExportTransport;
+pub use funnel::Funnel;
+#[cfg(feature = "grpc")]
+pub use grpc::GrpcTransport;
+pub use health::SelfTestReport;
+pub use interceptor::Interceptor;
+pub use micro::{MicroEvent, MicroValue};
+pub use network::NetworkConfig;
+pub use observer::{DropReason, EventObserver, EventStage};
+pub use overflow::OverflowPolicy;
+pub use perf::{PerfBudget, PerfReport};
+pub use power::PowerProfile;
+pub use priority::EventPriority;
+pub use purchase::{PurchaseReceipt, Store};
+pub use query::EventQuery;
+pub use ratelimit::RateLimiter;
+pub use resume::SessionManager;
+pub use retry::RetryPolicy;
+pub use sampling::{Sampler, SamplingMode, SamplingRule};
+pub use shared::SharedClient;
+pub use standard::ProgressionStatus;
+pub use storm::{StormGuard, StormGuardConfig};
+pub use summary::SessionSummary;
+pub use text::StringGuard;
+pub use transport::{
+    FileTransport, FlushResult, HttpTransport, MemoryTransport, TagFilter, Transport,
+    TransportResponse,
+};
+pub use unique::UniqueTracker;
+pub use validation::{EventLimits, EventSchema, PropertyType, SchemaRegistry, ValidationError};
+pub use wire::{WirePayloadV1, WirePayloadV2, WireVersion};
+pub use worker::GameEventsIOWorker;
+
+#[doc(hidden)]
+pub use serde_json as __serde_json;
+
+/// Default game-events.io ingestion endpoint
+pub const DEFAULT_BACKEND_URL: &str = "https://api.game-events.io/v1/events";
+
+/// Event sent when a session starts
+pub const EVENT_NEW_SESSION: &str = "new_session";
+
+/// Event sent when a session ends, carrying `SESSION_DURATION_PROPERTY`
+pub const EVENT_SESSION_END: &str = "session_end";
+
+/// Property of `session_end` with the session's length in seconds
+pub const SESSION_DURATION_PROPERTY: &str = "session_duration";
+
+/// Most dead-lettered events kept in memory
+const MAX_DEAD_LETTERS: usize = 1000;
+
+/// Most events per request sent by `shutdown`
+const SHUTDOWN_BATCH_SIZE: usize = 100;
+
+/// How long a flush waits on the developer mirror before giving up
+const MIRROR_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Current Unix timestamp in seconds
+pub(crate) fn unix_now() -> u64 {
+    unix_now_ms() / 1000
+}
+
+/// Current Unix timestamp in milliseconds
+pub(crate) fn unix_now_ms() -> u64 {
+    diagnostics::recover(
+        SystemTime::now().duration_since(UNIX_EPOCH),
+        "Time went backwards",
+        || Duration::ZERO,
+    )
+    .as_millis() as u64
+}
 
 /// Event structure for game-events.io
 #[derive(Serialize, Deserialize, Clone, Debug, Builder, Default)]
 #[builder(setter(into))]
 #[builder(default)]
+#[builder(build_fn(private, name = "build_event"))]
 pub struct GameEventsIOEvent {
     /// Event name (e.g., "level_completed", "purchase")
     pub event: String,
@@ -25,96 +171,507 @@ pub struct GameEventsIOEvent {
     #[builder(default = "self.default_time()")]
     pub time: u64,
 
+    /// Unix timestamp in milliseconds, if known; `time` is this value in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(setter(into, strip_option))]
+    pub time_ms: Option<u64>,
+
+    /// Position of the event among the ones logged by the client
+    ///
+    /// Set by the client when the event is buffered, so events logged in
+    /// the same millisecond can still be ordered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(setter(into, strip_option))]
+    pub seq: Option<u64>,
+
     /// Event-specific properties
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub event_properties: HashMap<String, serde_json::Value>,
 
     /// User properties (will be merged with existing user data)
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub user_properties: HashMap<String, serde_json::Value>,
+
+    /// Unix timestamp in seconds when the event's batch was sent
+    ///
+    /// Set by the client on every send attempt; `time` keeps the moment
+    /// the event was created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_upload_time: Option<u64>,
+
+    /// Unix timestamp in milliseconds when the event's batch was sent, if
+    /// known; `client_upload_time` is this value in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(setter(into, strip_option))]
+    pub client_upload_time_ms: Option<u64>,
+
+    /// Fields unknown to this SDK version, preserved across round-trips
+    ///
+    /// Lets events exported from the backend be edited and re-imported
+    /// without losing fields. Keys must not collide with the fields above.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+
+    /// Delivery priority, derived from the event name when not set
+    ///
+    /// Only used locally: kept in the offline queue, never sent to the backend.
+    #[serde(skip)]
+    #[builder(setter(into, strip_option))]
+    pub priority: Option<EventPriority>,
+
+    /// Free-form labels such as "qa" (see `flush_only` and `TagFilter`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[builder(setter(custom))]
+    pub tags: Vec<String>,
+
+    /// Store receipt of a purchase, verified by the backend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(setter(into, strip_option))]
+    pub receipt: Option<PurchaseReceipt>,
+}
+
+impl GameEventsIOEvent {
+    /// Delivery priority of this event
+    pub fn priority(&self) -> EventPriority {
+        self.priority
+            .unwrap_or_else(|| EventPriority::for_event_name(&self.event))
+    }
+
+    /// Add a tag (no-op if the event already has it)
+    pub fn tag(&mut self, tag: impl Into<String>) -> &mut Self {
+        let tag = tag.into();
+        if !self.has_tag(&tag) {
+            self.tags.push(tag);
+        }
+        self
+    }
+
+    /// Check whether the event has a tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
 }
 
 impl GameEventsIOEventBuilder {
+    /// Build the event
+    ///
+    /// Without an explicit `time` or `time_ms`, both are set from the same
+    /// reading of the clock.
+    pub fn build(&self) -> Result<GameEventsIOEvent, GameEventsIOEventBuilderError> {
+        let mut event = self.build_event()?;
+        match (self.time, self.time_ms) {
+            (None, None) => {
+                let now = unix_now_ms();
+                event.time = now / 1000;
+                event.time_ms = Some(now);
+            }
+            (None, Some(Some(time_ms))) => event.time = time_ms / 1000,
+            _ => {}
+        }
+        Ok(event)
+    }
+
     fn default_time(&self) -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("Time went backwards")
-            .as_secs()
+        unix_now()
+    }
+
+    /// Add a tag to the event
+    pub fn tag(&mut self, tag: impl Into<String>) -> &mut Self {
+        let tag = tag.into();
+        let tags = self.tags.get_or_insert_with(Vec::new);
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+        self
     }
 }
 
 use uuid::Uuid;
 
 /// Session structure that holds common properties for events
-#[derive(Clone, Debug, Builder)]
+///
+/// Events are pushed through `&self` into a lock-free queue, so one session
+/// can live in shared game state (e.g. an `Arc`) and be written from several
+/// systems and threads. The user id and user properties can be changed the
+/// same way; only the providers are set through `&mut self`, typically
+/// before the session is shared.
+#[derive(Debug, Builder)]
 #[builder(setter(into))]
+#[builder(build_fn(private, name = "build_session"))]
 pub struct GameEventsIOSession {
-    /// Unique user identifier
-    #[builder(default = "Uuid::new_v4().to_string()")]
-    user_id: String,
+    /// Unique user identifier, replaced by `identify`
+    #[builder(
+        setter(custom),
+        field(ty = "Option<String>", build = "RwLock::new(self.default_user_id())")
+    )]
+    user_id: RwLock<String>,
 
-    /// Session identifier
-    #[builder(default = "Uuid::new_v4().to_string()")]
-    session_id: String,
+    /// Session identifier; its write lock also serializes session rotation
+    #[builder(
+        setter(custom),
+        field(
+            ty = "Option<String>",
+            build = "RwLock::new(self.default_session_id())"
+        )
+    )]
+    session_id: RwLock<String>,
 
     /// Events
-    #[builder(default)]
-    events: Vec<GameEventsIOEvent>,
+    #[builder(field(ty = "Vec<GameEventsIOEvent>", build = "event_queue(&self.events)"))]
+    events: SegQueue<GameEventsIOEvent>,
 
     /// User properties that will be added to all events in this session
+    #[builder(
+        setter(custom),
+        field(
+            ty = "HashMap<String, serde_json::Value>",
+            build = "RwLock::new(self.user_properties.clone())"
+        )
+    )]
+    user_properties: RwLock<HashMap<String, serde_json::Value>>,
+
+    /// Allocation-free events, converted to full events when taken
+    #[builder(setter(skip))]
     #[builder(default)]
-    user_properties: HashMap<String, serde_json::Value>,
+    micro_events: Mutex<Vec<MicroEvent>>,
+
+    /// Source of subscription/DLC context attached to events
+    #[builder(default, setter(custom))]
+    entitlement_provider: Option<Arc<dyn EntitlementProvider>>,
+
+    /// Last entitlements reported by the provider, cleared when a session starts
+    #[builder(setter(skip))]
+    #[builder(default)]
+    entitlements: RwLock<Option<Entitlements>>,
+
+    /// Source of device/platform details attached to events
+    #[builder(default, setter(custom))]
+    context_provider: Option<Arc<dyn ContextProvider>>,
+
+    /// Device context reported by the provider
+    #[builder(setter(skip))]
+    #[builder(default)]
+    context: OnceLock<DeviceContext>,
+
+    /// Inactivity after which the next event starts a new session
+    #[builder(default, setter(into, strip_option))]
+    inactivity_timeout: Option<Duration>,
+
+    /// Unix timestamp the current session started at
+    #[builder(setter(skip))]
+    #[builder(default = "AtomicU64::new(unix_now())")]
+    started_at: AtomicU64,
+
+    /// Unix timestamp of the last event pushed to the session
+    #[builder(setter(skip))]
+    #[builder(default = "AtomicU64::new(unix_now())")]
+    last_activity: AtomicU64,
+
+    /// Whether `end_session` was called since the session started
+    #[builder(setter(skip))]
+    #[builder(default)]
+    ended: AtomicBool,
+
+    /// Aggregates of the current session, if `session_summary` is enabled
+    #[builder(
+        setter(custom),
+        field(ty = "bool", build = "self.summary.then(Mutex::default)")
+    )]
+    summary: Option<Mutex<SessionSummary>>,
+
+    /// Start times of running timed events, by event name
+    #[builder(setter(skip))]
+    #[builder(default)]
+    timers: Mutex<HashMap<String, Instant>>,
 }
 
-impl Default for GameEventsIOSession {
-    fn default() -> Self {
-        let mut session = GameEventsIOSessionBuilder::default()
-            .build()
-            .expect("Failed to create default GameEventsIOSession");
+impl GameEventsIOSessionBuilder {
+    /// Unique user identifier (default: a random UUID)
+    pub fn user_id(&mut self, user_id: impl Into<String>) -> &mut Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
 
-        // Auto-send new_session event
-        let mut props = HashMap::new();
-        props.insert(
-            "session_id".to_string(),
-            serde_json::json!(session.session_id.clone()),
-        );
-        session.push_event("new_session", props);
+    /// User properties that will be added to all events in this session
+    pub fn user_properties(
+        &mut self,
+        user_properties: HashMap<String, serde_json::Value>,
+    ) -> &mut Self {
+        self.user_properties = user_properties;
+        self
+    }
 
-        session
+    /// Session identifier (default: a random UUID)
+    pub fn session_id(&mut self, session_id: impl Into<String>) -> &mut Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Attach subscription/DLC context from the given provider to all events
+    pub fn entitlement_provider(
+        &mut self,
+        provider: impl EntitlementProvider + 'static,
+    ) -> &mut Self {
+        self.entitlement_provider = Some(Some(Arc::new(provider)));
+        self
+    }
+
+    /// Attach device/platform details from the given provider to all events
+    pub fn context_provider(&mut self, provider: impl ContextProvider + 'static) -> &mut Self {
+        self.context_provider = Some(Some(Arc::new(provider)));
+        self
+    }
+
+    /// Record a `session_summary` event with aggregates when each session ends
+    pub fn emit_summary(&mut self) -> &mut Self {
+        self.summary = true;
+        self
+    }
+
+    /// Build the session and record its `new_session` event
+    pub fn build(&self) -> Result<GameEventsIOSession, GameEventsIOSessionBuilderError> {
+        let session = self.build_session()?;
+        session.start(&session.current_session_id());
+        Ok(session)
+    }
+
+    fn default_user_id(&self) -> String {
+        self.user_id
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_string())
+    }
+
+    fn default_session_id(&self) -> String {
+        self.session_id
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_string())
+    }
+}
+
+/// Queue holding a copy of `events`, in order
+fn event_queue(events: &[GameEventsIOEvent]) -> SegQueue<GameEventsIOEvent> {
+    let queue = SegQueue::new();
+    for event in events {
+        queue.push(event.clone());
+    }
+    queue
+}
+
+/// Lock a mutex, even if a thread panicked while holding it
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Read-lock a `RwLock`, even if a thread panicked while holding it
+pub(crate) fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Write-lock a `RwLock`, even if a thread panicked while holding it
+pub(crate) fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Default for GameEventsIOSession {
+    fn default() -> Self {
+        diagnostics::recover(
+            GameEventsIOSessionBuilder::default().build(),
+            "Failed to create default GameEventsIOSession",
+            || {
+                GameEventsIOSession::started_fallback(
+                    Uuid::new_v4().to_string(),
+                    Uuid::new_v4().to_string(),
+                )
+            },
+        )
     }
 }
 
 impl GameEventsIOSession {
     /// Create a new session with user_id and session_id
     pub fn new(user_id: impl Into<String>, session_id: impl Into<String>) -> Self {
-        let mut session = GameEventsIOSessionBuilder::default()
-            .user_id(user_id)
-            .session_id(session_id)
-            .build()
-            .expect("Failed to create GameEventsIOSession");
+        let user_id = user_id.into();
+        let session_id = session_id.into();
+        diagnostics::recover(
+            GameEventsIOSessionBuilder::default()
+                .user_id(user_id.clone())
+                .session_id(session_id.clone())
+                .build(),
+            "Failed to create GameEventsIOSession",
+            || Self::started_fallback(user_id, session_id),
+        )
+    }
+
+    /// Send the new_session event and reset the session clock
+    fn start(&self, session_id: &str) {
+        let now = unix_now();
+        self.started_at.store(now, Ordering::Relaxed);
+        self.last_activity.store(now, Ordering::Relaxed);
+        self.ended.store(false, Ordering::Relaxed);
+        // Subscriptions may have changed since the last session
+        *write(&self.entitlements) = None;
+
+        let mut props = HashMap::new();
+        props.insert("session_id".to_string(), serde_json::json!(session_id));
+        self.record(self.build_event(session_id, EVENT_NEW_SESSION, props));
+    }
+
+    /// Send the session_summary (if enabled) and session_end events
+    fn finish(&self, session_id: &str, ended_at: u64) {
+        self.drain_micro_events(session_id);
+        let playtime = ended_at.saturating_sub(self.started_at.load(Ordering::Relaxed));
+        if let Some(counters) = &self.summary {
+            let aggregates = std::mem::take(&mut *lock(counters));
+            let mut event = self.build_event(
+                session_id,
+                summary::EVENT_SESSION_SUMMARY,
+                aggregates.properties(playtime),
+            );
+            event.priority = Some(EventPriority::Critical);
+            self.record(event);
+        }
 
-        // Auto-send new_session event
         let mut props = HashMap::new();
         props.insert(
-            "session_id".to_string(),
-            serde_json::json!(session.session_id.clone()),
+            SESSION_DURATION_PROPERTY.to_string(),
+            serde_json::json!(playtime),
         );
-        session.push_event("new_session", props);
+        self.record(self.build_event(session_id, EVENT_SESSION_END, props));
+        self.ended.store(true, Ordering::Relaxed);
+    }
+
+    /// End the current session (e.g. when the game quits or goes to background)
+    ///
+    /// The next event pushed afterwards starts a new session.
+    pub fn end_session(&self) {
+        let session_id = self.write_session_id();
+        if !self.ended.load(Ordering::Relaxed) {
+            self.finish(&session_id, unix_now());
+        }
+    }
+
+    /// End the current session and start a new one with a fresh session_id
+    pub fn rotate_session(&self) {
+        let mut session_id = self.write_session_id();
+        if !self.ended.load(Ordering::Relaxed) {
+            self.finish(&session_id, unix_now());
+        }
+        *session_id = Uuid::new_v4().to_string();
+        self.start(&session_id);
+    }
 
+    /// Start a new session if the current one ended or timed out
+    ///
+    /// Called by `push_event`; call it when the game returns from background
+    /// to rotate before the next event. Returns whether a new session started.
+    pub fn check_session(&self) -> bool {
+        if !self.needs_rotation() {
+            return false;
+        }
+        let mut session_id = self.write_session_id();
+        // Another thread may have started the new session in the meantime
+        if !self.needs_rotation() {
+            return false;
+        }
+        if !self.ended.load(Ordering::Relaxed) {
+            // Idle time after the last event doesn't count toward the session
+            self.finish(&session_id, self.last_activity.load(Ordering::Relaxed));
+        }
+        *session_id = Uuid::new_v4().to_string();
+        self.start(&session_id);
+        true
+    }
+
+    /// Whether the current session ended or timed out
+    fn needs_rotation(&self) -> bool {
+        let idle = unix_now().saturating_sub(self.last_activity.load(Ordering::Relaxed));
+        self.ended.load(Ordering::Relaxed)
+            || self
+                .inactivity_timeout
+                .is_some_and(|timeout| idle >= timeout.as_secs())
+    }
+
+    /// Time since the current session started
+    pub fn session_duration(&self) -> Duration {
+        let started_at = self.started_at.load(Ordering::Relaxed);
+        Duration::from_secs(unix_now().saturating_sub(started_at))
+    }
+
+    /// Aggregates of the current session so far, if `emit_summary` is enabled
+    pub fn summary(&self) -> Option<SessionSummary> {
+        self.summary.as_ref().map(|counters| lock(counters).clone())
+    }
+
+    /// Bare session used if building one fails under `PanicPolicy::Degrade`
+    fn fallback(user_id: String, session_id: String) -> Self {
+        Self {
+            user_id: RwLock::new(user_id),
+            session_id: RwLock::new(session_id),
+            events: SegQueue::new(),
+            user_properties: RwLock::default(),
+            micro_events: Mutex::default(),
+            entitlement_provider: None,
+            entitlements: RwLock::default(),
+            context_provider: None,
+            context: OnceLock::new(),
+            inactivity_timeout: None,
+            started_at: AtomicU64::new(unix_now()),
+            last_activity: AtomicU64::new(unix_now()),
+            ended: AtomicBool::new(false),
+            summary: None,
+            timers: Mutex::default(),
+        }
+    }
+
+    /// Bare session with its `new_session` event recorded
+    fn started_fallback(user_id: String, session_id: String) -> Self {
+        let session = Self::fallback(user_id, session_id);
+        session.start(&session.current_session_id());
         session
     }
 
     /// Add an event to the session
+    ///
+    /// Starts a new session first if the current one ended or timed out.
     pub fn push_event(
-        &mut self,
+        &self,
         event: impl Into<String>,
         event_properties: HashMap<String, serde_json::Value>,
     ) {
+        self.check_session();
+        let session_id = self.current_session_id();
+        self.drain_micro_events(&session_id);
+        self.record(self.build_event(&session_id, event, event_properties));
+        self.last_activity.store(unix_now(), Ordering::Relaxed);
+    }
+
+    /// Queue an event, counting it in the summary
+    fn record(&self, event: GameEventsIOEvent) {
+        if let Some(counters) = &self.summary {
+            let lifecycle = [
+                EVENT_NEW_SESSION,
+                EVENT_SESSION_END,
+                summary::EVENT_SESSION_SUMMARY,
+            ];
+            if !lifecycle.contains(&event.event.as_str()) {
+                lock(counters).record(&event);
+            }
+        }
+        self.events.push(event);
+    }
+
+    /// Build an event of the session, without checking the session lifecycle
+    fn build_event(
+        &self,
+        session_id: &str,
+        event: impl Into<String>,
+        event_properties: HashMap<String, serde_json::Value>,
+    ) -> GameEventsIOEvent {
         // Determine user_id: check properties first, then session
         let user_id = if let Some(uid) = event_properties.get("user_id").and_then(|v| v.as_str()) {
             uid.to_string()
         } else {
-            self.user_id.clone()
+            self.user_id()
         };
 
         // Determine session_id: check properties first, then session
@@ -122,285 +679,2483 @@ impl GameEventsIOSession {
             if let Some(sid) = event_properties.get("session_id").and_then(|v| v.as_str()) {
                 sid.to_string()
             } else {
-                self.session_id.clone()
+                session_id.to_string()
             };
 
         // Create the event
-        let event = GameEventsIOEventBuilder::default()
-            .event(event)
-            .user_id(user_id)
-            .session_id(session_id)
-            .user_properties(self.user_properties.clone())
-            .event_properties(event_properties)
-            .build()
-            .expect("Failed to build event");
+        diagnostics::recover(
+            GameEventsIOEventBuilder::default()
+                .event(event)
+                .user_id(user_id)
+                .session_id(session_id)
+                .user_properties(self.event_user_properties())
+                .event_properties(event_properties)
+                .build(),
+            "Failed to build event",
+            GameEventsIOEvent::default,
+        )
+    }
 
-        self.events.push(event);
+    /// User properties attached to new events, including entitlements
+    ///
+    /// Queries the entitlement provider the first time it is needed in a session.
+    fn event_user_properties(&self) -> HashMap<String, serde_json::Value> {
+        let mut user_properties = self.user_properties();
+        if let Some(provider) = &self.entitlement_provider {
+            write(&self.entitlements)
+                .get_or_insert_with(|| provider.entitlements())
+                .apply(&mut user_properties);
+        }
+        if let Some(provider) = &self.context_provider {
+            self.context
+                .get_or_init(|| provider.context())
+                .apply(&mut user_properties);
+        }
+        user_properties
+    }
+
+    /// Set the provider of device/platform details
+    pub fn set_context_provider(&mut self, provider: impl ContextProvider + 'static) {
+        self.context_provider = Some(Arc::new(provider));
+        self.context = OnceLock::new();
+    }
+
+    /// Get the device context currently attached to events
+    pub fn device_context(&self) -> Option<&DeviceContext> {
+        self.context.get()
+    }
+
+    /// Set the provider of subscription/DLC context and query it right away
+    pub fn set_entitlement_provider(&mut self, provider: impl EntitlementProvider + 'static) {
+        self.entitlement_provider = Some(Arc::new(provider));
+        self.refresh_entitlements();
+    }
+
+    /// Query the entitlement provider again (e.g. after a purchase)
+    ///
+    /// Also done automatically whenever a new session starts.
+    pub fn refresh_entitlements(&self) {
+        *write(&self.entitlements) = self
+            .entitlement_provider
+            .as_ref()
+            .map(|provider| provider.entitlements());
+    }
+
+    /// Get the entitlements currently attached to events
+    ///
+    /// Returns a copy, since a new session may query them again.
+    pub fn entitlements(&self) -> Option<Entitlements> {
+        read(&self.entitlements).clone()
     }
 
     /// Add or update a user property for this session
-    pub fn set_user_property(&mut self, key: impl Into<String>, value: serde_json::Value) {
-        self.user_properties.insert(key.into(), value);
+    pub fn set_user_property(&self, key: impl Into<String>, value: serde_json::Value) {
+        write(&self.user_properties).insert(key.into(), value);
     }
 
     /// Set new user properties
-    pub fn set_user_properties(&mut self, user_properties: HashMap<String, serde_json::Value>) {
-        self.user_properties = user_properties;
+    pub fn set_user_properties(&self, user_properties: HashMap<String, serde_json::Value>) {
+        *write(&self.user_properties) = user_properties;
     }
 
     /// Get the user_id for this session
-    pub fn user_id(&self) -> &str {
-        &self.user_id
+    ///
+    /// Returns a copy, since another thread may call `identify`.
+    pub fn user_id(&self) -> String {
+        read(&self.user_id).clone()
     }
 
     /// Get the session_id for this session
-    pub fn session_id(&self) -> &str {
-        &self.session_id
+    ///
+    /// Returns a copy, since another thread may rotate the session.
+    pub fn session_id(&self) -> String {
+        self.current_session_id().clone()
+    }
+
+    fn current_session_id(&self) -> RwLockReadGuard<'_, String> {
+        read(&self.session_id)
+    }
+
+    fn write_session_id(&self) -> RwLockWriteGuard<'_, String> {
+        write(&self.session_id)
     }
 
     /// Get all user properties for this session
-    pub fn user_properties(&self) -> &HashMap<String, serde_json::Value> {
-        &self.user_properties
+    ///
+    /// Returns a copy, since another thread may change them.
+    pub fn user_properties(&self) -> HashMap<String, serde_json::Value> {
+        read(&self.user_properties).clone()
+    }
+
+    /// Add an allocation-free event to the session
+    ///
+    /// Like `push_event`, starts a new session first if the current one
+    /// ended or timed out. Does not allocate as long as the capacity reserved
+    /// with `reserve_micro_events` is not exceeded.
+    pub fn push_micro_event(&self, event: MicroEvent) {
+        self.check_session();
+        // Holding the session id keeps a rotation from slipping in between
+        let _session_id = self.current_session_id();
+        lock(&self.micro_events).push(event);
+        self.last_activity.store(unix_now(), Ordering::Relaxed);
+    }
+
+    /// Reserve room for `additional` micro events up front
+    pub fn reserve_micro_events(&self, additional: usize) {
+        lock(&self.micro_events).reserve(additional);
+    }
+
+    /// Get the number of events waiting to be taken
+    pub fn pending_events_count(&self) -> usize {
+        self.events.len() + lock(&self.micro_events).len()
+    }
+
+    /// Convert pending micro events to full events of `session_id`
+    ///
+    /// Called before any other event is queued and when the session ends, so
+    /// micro events keep their order and belong to the session they were
+    /// pushed in. They are not counted in the summary.
+    fn drain_micro_events(&self, session_id: &str) {
+        let mut micro_events = lock(&self.micro_events);
+        if micro_events.is_empty() {
+            return;
+        }
+        let user_id = self.user_id();
+        let user_properties = self.event_user_properties();
+        for micro in micro_events.drain(..) {
+            self.events
+                .push(micro.into_event(&user_id, session_id, &user_properties));
+        }
     }
 
     /// Take all events from this session
-    pub fn take_events(&mut self, max_count: usize) -> Vec<GameEventsIOEvent> {
-        let count = std::cmp::min(self.events.len(), max_count);
-        self.events.drain(0..count).collect()
+    ///
+    /// Pending micro events are converted first.
+    pub fn take_events(&self, max_count: usize) -> Vec<GameEventsIOEvent> {
+        self.drain_micro_events(&self.current_session_id());
+
+        std::iter::from_fn(|| self.events.pop())
+            .take(max_count)
+            .collect()
     }
 }
 
 /// game-events.io SDK client
-#[derive(Debug, Clone, Builder)]
+///
+/// Not `Clone`: use `into_shared` to log into one buffer from several places.
+#[derive(Debug, Builder)]
 #[builder(setter(into))]
+#[builder(build_fn(private, name = "build_client"))]
 pub struct GameEventsIOClient {
     /// API key for authentication
     api_key: String,
 
     /// Backend URL (default: https://api.game-events.io/v1/events)
-    #[builder(default = "\"https://api.game-events.io/v1/events\".to_string()")]
+    #[builder(default = "DEFAULT_BACKEND_URL.to_string()")]
     backend_url: String,
 
-    /// HTTP client for making requests
+    /// Compression of request bodies sent by the default HTTP transport
+    #[builder(default)]
+    compression: Compression,
+
+    /// Custom codec used instead of `compression`, if set
+    #[builder(default, setter(custom))]
+    compressor: Option<Arc<dyn Compressor>>,
+
+    /// Whether the default HTTP transport sends the dictionary envelope
+    #[builder(default)]
+    dictionary_encoding: bool,
+
+    /// Whether the default HTTP transport sends batches as NDJSON
+    #[builder(default)]
+    ndjson_uploads: bool,
+
+    /// Payload format sent by the default HTTP transport (default: V1)
+    #[builder(default)]
+    wire_version: WireVersion,
+
+    /// TLS, proxy and timeout settings of the SDK's HTTP requests
+    #[builder(default, setter(custom))]
+    network_config: NetworkConfig,
+
+    /// Whether uploads carry timestamps corrected by `clock_offset_ms`
+    #[builder(default)]
+    correct_clock_skew: bool,
+
+    /// Backend clock minus local clock in milliseconds, once a response reported it
     #[builder(setter(skip))]
-    #[builder(
-        default = "reqwest::blocking::Client::builder().danger_accept_invalid_certs(true).build().unwrap()"
-    )]
-    client: reqwest::blocking::Client,
+    #[builder(default)]
+    clock_offset_ms: Option<i64>,
 
-    /// Buffered events waiting to be sent
+    /// Sequence number of the next buffered event
     #[builder(setter(skip))]
     #[builder(default)]
-    events: Vec<GameEventsIOEvent>,
-}
+    next_seq: u64,
 
-impl GameEventsIOClient {
-    /// Create a new GameEventsIO client
-    pub fn new(api_key: impl Into<String>) -> Self {
-        GameEventsIOClientBuilder::default()
-            .api_key(api_key)
-            .build()
-            .expect("Failed to create GameEventsIOClient")
-    }
+    /// Transport used to send batches (default: HTTP to `backend_url`)
+    #[builder(setter(custom))]
+    #[builder(default = "self.default_transport()")]
+    transport: Arc<dyn Transport>,
 
-    /// Log an event (adds to buffer)
-    pub fn log_event(&mut self, event: GameEventsIOEvent) {
-        self.events.push(event);
-    }
+    /// Local endpoint every sent batch is also posted to
+    #[builder(default, setter(custom))]
+    mirror: Option<HttpTransport>,
 
-    /// Send all buffered events to the backend
-    pub fn flush(&mut self) -> Result<String, reqwest::Error> {
-        if self.events.is_empty() {
-            return Ok("No events to send".to_string());
-        }
+    /// Buffered events waiting to be sent
+    #[builder(setter(skip))]
+    #[builder(default = "self.default_events()")]
+    events: Vec<GameEventsIOEvent>,
 
-        let events_to_send: Vec<GameEventsIOEvent> = self.events.drain(..).collect();
+    /// Upper bound on `events`, unbounded if not set
+    #[builder(default, setter(into, strip_option))]
+    max_buffered_events: Option<usize>,
 
-        let response = self
-            .client
-            .post(&self.backend_url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&events_to_send)
-            .send()?
-            .text()?;
+    /// What happens to events once `max_buffered_events` is reached
+    #[builder(default)]
+    overflow_policy: OverflowPolicy,
 
-        Ok(response)
-    }
+    /// Number of events discarded because the buffer was full
+    #[builder(setter(skip))]
+    #[builder(default)]
+    dropped_events: u64,
 
-    /// Send events in batches (useful for large event counts)
-    pub fn flush_batch(&mut self, batch_size: usize) -> Result<String, reqwest::Error> {
-        if self.events.is_empty() {
-            return Ok("No events to send".to_string());
-        }
+    /// Disk-backed copy of `events`, if enabled
+    #[builder(default, setter(custom))]
+    queue: Option<queue::EventQueue>,
 
-        let events_to_send: Vec<GameEventsIOEvent> = if self.events.len() > batch_size {
-            self.events.drain(..batch_size).collect()
-        } else {
-            self.events.drain(..).collect()
-        };
+    /// Optional client-side sampling
+    #[builder(default, setter(into, strip_option))]
+    sampler: Option<Sampler>,
 
-        let response = self
-            .client
-            .post(&self.backend_url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&events_to_send)
-            .send()?
-            .text()?;
+    /// Number of events dropped by sampling, per event name
+    #[builder(setter(skip))]
+    #[builder(default)]
+    sampled_out: HashMap<String, u64>,
 
-        Ok(response)
-    }
+    /// Optional cap on the number of events logged per minute
+    #[builder(default, setter(into, strip_option))]
+    rate_limiter: Option<RateLimiter>,
 
-    /// Get the number of buffered events
-    pub fn pending_events_count(&self) -> usize {
-        self.events.len()
-    }
-}
+    /// Number of events dropped by the rate limiter
+    #[builder(setter(skip))]
+    #[builder(default)]
+    rate_limited: u64,
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Optional circuit breaker that collapses event storms
+    #[builder(default, setter(into, strip_option))]
+    storm_guard: Option<StormGuard>,
 
-    #[test]
-    fn test_event_creation() {
-        let event = GameEventsIOEventBuilder::default()
-            .event("test_event")
-            .user_id("user123")
-            .session_id("session456")
-            .build()
-            .unwrap();
+    /// Optional cleanup of string properties
+    #[builder(default, setter(into, strip_option))]
+    string_guard: Option<StringGuard>,
 
-        assert_eq!(event.event, "test_event");
-        assert_eq!(event.user_id, "user123");
-        assert_eq!(event.session_id, "session456");
-        assert!(event.time > 0);
-    }
+    /// Optional validation of events at log time
+    #[builder(default, setter(into, strip_option))]
+    schema_registry: Option<SchemaRegistry>,
 
-    #[test]
-    fn test_client_creation() {
-        let client = GameEventsIOClient::new("test_api_key");
-        assert_eq!(client.pending_events_count(), 0);
-    }
+    /// Hooks run on every event before it is buffered, in order
+    #[builder(default, setter(custom))]
+    interceptors: Vec<Arc<dyn Interceptor>>,
 
-    #[test]
-    fn test_event_buffering() {
-        let mut client = GameEventsIOClient::new("test_api_key");
+    /// Callbacks told about every event at each stage of the pipeline
+    #[builder(default, setter(custom))]
+    observers: Vec<Arc<dyn EventObserver>>,
 
-        let event = GameEventsIOEventBuilder::default()
-            .event("test_event")
-            .user_id("user123")
-            .session_id("session456")
-            .build()
-            .unwrap();
+    /// Property keys removed from every event at log time
+    #[builder(default, setter(custom))]
+    redacted_properties: Vec<String>,
 
-        client.log_event(event);
-        assert_eq!(client.pending_events_count(), 1);
-    }
+    /// Endpoint of `request_user_data_deletion`
+    #[builder(default = "privacy::DEFAULT_DELETION_URL.to_string()")]
+    deletion_url: String,
+
+    /// Directory where the SDK persists its state between runs
+    #[builder(default, setter(into, strip_option))]
+    storage_dir: Option<PathBuf>,
+
+    /// Whether the player has opted out of tracking
+    #[builder(setter(skip))]
+    #[builder(default = "self.default_opted_out()")]
+    opted_out: bool,
+
+    /// Reason this run is automated, if it was marked as such
+    #[builder(setter(skip))]
+    #[builder(default)]
+    automation_reason: Option<String>,
+
+    /// How events of automated runs are treated
+    #[builder(default)]
+    automation_suppression: AutomationSuppression,
+
+    /// How failed batches are retried
+    #[builder(default)]
+    retry_policy: RetryPolicy,
+
+    /// How long `log_purchase` remembers transaction ids
+    #[builder(default = "purchase::DEFAULT_PURCHASE_DEDUPE_WINDOW")]
+    purchase_dedupe_window: Duration,
+
+    /// Transaction ids already logged by `log_purchase`
+    #[builder(setter(skip))]
+    #[builder(default = "self.default_purchase_dedupe()")]
+    purchase_dedupe: purchase::PurchaseDedupe,
+
+    /// Whether uploads are paused because the backend rejected the API key
+    #[builder(setter(skip))]
+    #[builder(default)]
+    paused: bool,
+
+    /// Events the backend refused for good, most recent last
+    #[builder(setter(skip))]
+    #[builder(default)]
+    dead_letters: Vec<GameEventsIOEvent>,
+}
+
+impl GameEventsIOClientBuilder {
+    /// Send batches through a custom transport instead of HTTP
+    pub fn transport(&mut self, transport: impl Transport + 'static) -> &mut Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Compress uploads of the default HTTP transport with a custom codec
+    ///
+    /// Takes precedence over `compression`.
+    pub fn compressor(&mut self, compressor: impl Compressor + 'static) -> &mut Self {
+        self.compressor = Some(Some(Arc::new(compressor)));
+        self
+    }
+
+    /// Replace all TLS, proxy and timeout settings of the SDK's HTTP requests
+    pub fn network_config(&mut self, config: NetworkConfig) -> &mut Self {
+        self.network_config = Some(config);
+        self
+    }
+
+    /// Accept invalid server certificates (default: `false`)
+    ///
+    /// Only for local test backends with self-signed certificates; never
+    /// enable it in release builds.
+    pub fn accept_invalid_certs(&mut self, accept: bool) -> &mut Self {
+        self.network_config_mut().accept_invalid_certs = accept;
+        self
+    }
+
+    /// Trust an extra root certificate, PEM encoded
+    pub fn root_certificate_pem(&mut self, pem: impl Into<Vec<u8>>) -> &mut Self {
+        self.network_config_mut().root_certificates.push(pem.into());
+        self
+    }
+
+    /// Send every request through a proxy (e.g. "http://proxy.local:3128")
+    pub fn proxy(&mut self, url: impl Into<String>) -> &mut Self {
+        self.network_config_mut().proxy = Some(url.into());
+        self
+    }
+
+    /// Give up on requests that take longer than `timeout`
+    pub fn request_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.network_config_mut().timeout = Some(timeout);
+        self
+    }
+
+    fn network_config_mut(&mut self) -> &mut NetworkConfig {
+        self.network_config
+            .get_or_insert_with(NetworkConfig::default)
+    }
+
+    /// Run an interceptor on every event before it is buffered
+    ///
+    /// Interceptors run in the order they were added.
+    pub fn interceptor(&mut self, interceptor: impl Interceptor + 'static) -> &mut Self {
+        self.interceptors
+            .get_or_insert_with(Vec::new)
+            .push(Arc::new(interceptor));
+        self
+    }
+
+    /// Tell an observer about every event at each stage of the pipeline
+    pub fn observer(&mut self, observer: impl EventObserver + 'static) -> &mut Self {
+        self.observers
+            .get_or_insert_with(Vec::new)
+            .push(Arc::new(observer));
+        self
+    }
+
+    /// Remove a property key (e.g. "email") from every event before it is buffered
+    ///
+    /// Applies to both event and user properties.
+    pub fn redact_property(&mut self, key: impl Into<String>) -> &mut Self {
+        self.redacted_properties
+            .get_or_insert_with(Vec::new)
+            .push(key.into());
+        self
+    }
+
+    /// Also post every sent batch to a local endpoint (e.g. `http://127.0.0.1:7788`)
+    ///
+    /// Meant for developer builds, so desktop tools can show live telemetry.
+    /// The mirror gets every request as sent, retries included, except during
+    /// `shutdown`. Failures are ignored, but an endpoint that doesn't answer
+    /// holds up each request for up to a second.
+    ///
+    /// Does nothing in release builds (without `debug_assertions`), so player
+    /// payloads are never copied to a local sink.
+    pub fn mirror_to(&mut self, url: impl Into<String>) -> &mut Self {
+        if cfg!(debug_assertions) {
+            self.mirror = Some(Some(HttpTransport::new(url, "").timeout(MIRROR_TIMEOUT)));
+        }
+        self
+    }
+
+    /// Keep unsent events in a JSON lines file, so they survive crashes and offline play
+    ///
+    /// Events are appended as they are logged and removed only after a
+    /// successful send. Events left over from a previous run are loaded on
+    /// build.
+    pub fn persist_to(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.queue = Some(Some(queue::EventQueue::new(path.into())));
+        self
+    }
+
+    /// Build the client, picking up events left in the offline queue
+    ///
+    /// Restored events keep their sequence numbers; new events are
+    /// numbered after them.
+    pub fn build(&self) -> Result<GameEventsIOClient, GameEventsIOClientBuilderError> {
+        let mut client = self.build_client()?;
+
+        // Events restored from the offline queue count toward the buffer limit
+        let mut restored = std::mem::take(&mut client.events);
+        let restored_count = restored.len();
+        client.make_room(&mut restored);
+        client.events = restored;
+        if client.events.len() < restored_count {
+            client.sync_queue();
+        }
+
+        client.next_seq = client
+            .events
+            .iter()
+            .filter_map(|event| event.seq)
+            .max()
+            .map_or(0, |seq| seq + 1);
+        client.mirror = client
+            .mirror
+            .take()
+            .map(|mirror| mirror.wire_version(client.wire_version));
+        Ok(client)
+    }
+
+    fn default_events(&self) -> Vec<GameEventsIOEvent> {
+        match &self.queue {
+            Some(Some(queue)) => queue.restore(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn default_transport(&self) -> Arc<dyn Transport> {
+        let backend_url = self
+            .backend_url
+            .clone()
+            .unwrap_or_else(|| DEFAULT_BACKEND_URL.to_string());
+        let api_key = self.api_key.clone().unwrap_or_default();
+        let network_config = self.network_config.clone().unwrap_or_default();
+        let transport = HttpTransport::with_network_config(backend_url, api_key, network_config)
+            .compression(self.compression.unwrap_or_default())
+            .dictionary_encoding(self.dictionary_encoding.unwrap_or_default())
+            .ndjson(self.ndjson_uploads.unwrap_or_default())
+            .wire_version(self.wire_version.unwrap_or_default());
+        match &self.compressor {
+            Some(Some(compressor)) => Arc::new(transport.compressor(compressor.clone())),
+            _ => Arc::new(transport),
+        }
+    }
+
+    fn default_opted_out(&self) -> bool {
+        match &self.storage_dir {
+            Some(Some(dir)) => storage::flag_exists(dir, storage::OPT_OUT_FILE),
+            _ => false,
+        }
+    }
+
+    fn default_purchase_dedupe(&self) -> purchase::PurchaseDedupe {
+        match &self.storage_dir {
+            Some(Some(dir)) => purchase::PurchaseDedupe::load(dir.clone()),
+            _ => purchase::PurchaseDedupe::default(),
+        }
+    }
+}
+
+impl GameEventsIOClient {
+    /// Create a new GameEventsIO client
+    pub fn new(api_key: impl Into<String>) -> Self {
+        let api_key = api_key.into();
+        diagnostics::recover(
+            GameEventsIOClientBuilder::default()
+                .api_key(api_key.clone())
+                .build(),
+            "Failed to create GameEventsIOClient",
+            || Self::disabled(api_key),
+        )
+    }
+
+    /// Turn the client into a handle that can be cloned and sent to other threads
+    pub fn into_shared(self) -> SharedClient {
+        SharedClient::new(self)
+    }
+
+    /// No-op client used if building one fails under `PanicPolicy::Degrade`
+    ///
+    /// Behaves as if the player opted out, without touching the disk.
+    fn disabled(api_key: String) -> Self {
+        Self {
+            api_key,
+            backend_url: DEFAULT_BACKEND_URL.to_string(),
+            compression: Compression::None,
+            compressor: None,
+            dictionary_encoding: false,
+            ndjson_uploads: false,
+            wire_version: WireVersion::V1,
+            network_config: NetworkConfig::default(),
+            correct_clock_skew: false,
+            clock_offset_ms: None,
+            next_seq: 0,
+            transport: Arc::new(MemoryTransport::new()),
+            mirror: None,
+            events: Vec::new(),
+            max_buffered_events: Some(0),
+            overflow_policy: OverflowPolicy::DropNewest,
+            dropped_events: 0,
+            queue: None,
+            sampler: None,
+            sampled_out: HashMap::new(),
+            rate_limiter: None,
+            rate_limited: 0,
+            storm_guard: None,
+            string_guard: None,
+            schema_registry: None,
+            interceptors: Vec::new(),
+            observers: Vec::new(),
+            redacted_properties: Vec::new(),
+            deletion_url: privacy::DEFAULT_DELETION_URL.to_string(),
+            storage_dir: None,
+            opted_out: true,
+            automation_reason: None,
+            automation_suppression: AutomationSuppression::default(),
+            retry_policy: RetryPolicy::none(),
+            purchase_dedupe_window: purchase::DEFAULT_PURCHASE_DEDUPE_WINDOW,
+            purchase_dedupe: purchase::PurchaseDedupe::default(),
+            paused: false,
+            dead_letters: Vec::new(),
+        }
+    }
+
+    /// Log an event (adds to buffer)
+    ///
+    /// Events failing the schema registry are dropped and reported to the
+    /// error callback; use `try_log_event` to get the error instead.
+    pub fn log_event(&mut self, event: GameEventsIOEvent) {
+        if let Err(e) = self.try_log_event(event) {
+            diagnostics::report(&e);
+        }
+    }
+
+    /// Log an event, returning why it was rejected if it fails validation
+    pub fn try_log_event(&mut self, event: GameEventsIOEvent) -> Result<(), GameEventsIOError> {
+        self.buffer_event(event).map(|_| ())
+    }
+
+    /// Log an event, returning whether it ended up in the buffer
+    fn buffer_event(&mut self, mut event: GameEventsIOEvent) -> Result<bool, GameEventsIOError> {
+        if self.opted_out {
+            return Ok(false);
+        }
+
+        observer::notify(&self.observers, EventStage::Logged, [&event]);
+        if !interceptor::run(&self.interceptors, &mut event) {
+            let stage = EventStage::Dropped(DropReason::Interceptor);
+            observer::notify(&self.observers, stage, [&event]);
+            return Ok(false);
+        }
+        privacy::redact(&mut event, &self.redacted_properties);
+
+        if let Some(guard) = &self.string_guard {
+            guard.apply(&mut event);
+        }
+
+        if let Some(registry) = &self.schema_registry {
+            if let Err(errors) = registry.validate(&event) {
+                observer::notify(&self.observers, EventStage::Rejected, [&event]);
+                return Err(GameEventsIOError::Validation(errors));
+            }
+        }
+
+        observer::notify(&self.observers, EventStage::Validated, [&event]);
+        Ok(self.admit(event))
+    }
+
+    /// Run an interceptor on every event logged from now on, after the existing ones
+    pub fn add_interceptor(&mut self, interceptor: impl Interceptor + 'static) {
+        self.interceptors.push(Arc::new(interceptor));
+    }
+
+    /// Run an observer on every event from now on
+    pub fn observe(&mut self, observer: Box<dyn EventObserver>) {
+        self.observers.push(Arc::from(observer));
+    }
+
+    /// Apply sampling, automation tagging and the storm guard, then buffer
+    ///
+    /// Returns whether the event was buffered.
+    fn admit(&mut self, mut event: GameEventsIOEvent) -> bool {
+        // Critical events, such as purchases and session summaries, are never sampled out
+        if let Some(sampler) = self
+            .sampler
+            .as_ref()
+            .filter(|_| event.priority() < EventPriority::Critical)
+        {
+            if !sampler.sample(&mut event) {
+                let stage = EventStage::Dropped(DropReason::Sampled);
+                observer::notify(&self.observers, stage, [&event]);
+                *self.sampled_out.entry(event.event).or_default() += 1;
+                return false;
+            }
+        }
+
+        if let Some(limiter) = self.rate_limiter.as_mut() {
+            if event.priority() < EventPriority::Critical && !limiter.try_acquire() {
+                let stage = EventStage::Dropped(DropReason::RateLimited);
+                observer::notify(&self.observers, stage, [&event]);
+                self.rate_limited += 1;
+                return false;
+            }
+        }
+
+        if let Some(reason) = &self.automation_reason {
+            if !automation::tag_event(&mut event, reason, self.automation_suppression) {
+                let stage = EventStage::Dropped(DropReason::Automation);
+                observer::notify(&self.observers, stage, [&event]);
+                return false;
+            }
+        }
+
+        let (admitted, suppressed) = match self.storm_guard.as_mut() {
+            Some(guard) => {
+                let name = event.event.clone();
+                let admitted = guard.admit(event);
+                (admitted, guard.is_storming(&name))
+            }
+            None => (vec![event], false),
+        };
+        // Without a storm, the event is the last one admitted
+        self.enqueue(admitted) && !suppressed
+    }
+
+    /// Emit summaries for event storms that have subsided
+    fn settle_storms(&mut self) {
+        if let Some(guard) = self.storm_guard.as_mut() {
+            let summaries = guard.settle(unix_now());
+            self.enqueue(summaries);
+        }
+    }
+
+    /// Buffer events, appending them to the persistent queue if enabled
+    ///
+    /// Returns whether the last of `events` was buffered.
+    fn enqueue(&mut self, mut events: Vec<GameEventsIOEvent>) -> bool {
+        let incoming = events.len();
+        let evicted = self.make_room(&mut events);
+        // `DropOldest` drops the front of `events`, the other policies the back
+        let last_kept = match self.overflow_policy {
+            OverflowPolicy::DropOldest => !events.is_empty(),
+            _ => incoming > 0 && events.len() == incoming,
+        };
+        if events.is_empty() && !evicted {
+            return last_kept;
+        }
+
+        for event in events.iter_mut() {
+            event.seq = Some(self.next_seq);
+            self.next_seq += 1;
+        }
+        let start = self.events.len();
+        self.events.extend(events);
+        if evicted {
+            self.sync_queue();
+        } else if let Some(queue) = &self.queue {
+            // Events stay buffered in memory even if the disk write fails
+            if let Err(e) = queue.append(&self.events[start..]) {
+                diagnostics::report(&e.into());
+            }
+        }
+        last_kept
+    }
+
+    /// Apply the overflow policy before buffering `incoming`
+    ///
+    /// Returns whether buffered events were evicted.
+    fn make_room(&mut self, incoming: &mut Vec<GameEventsIOEvent>) -> bool {
+        let Some(max) = self.max_buffered_events else {
+            return false;
+        };
+        let overflow = |buffered: usize, incoming: usize| (buffered + incoming).saturating_sub(max);
+        if overflow(self.events.len(), incoming.len()) == 0 {
+            return false;
+        }
+
+        let mut evicted = false;
+        match self.overflow_policy {
+            OverflowPolicy::DropOldest => {
+                let count = overflow(self.events.len(), incoming.len()).min(self.events.len());
+                let oldest: Vec<_> = self.events.drain(..count).collect();
+                let stage = EventStage::Dropped(DropReason::BufferFull);
+                observer::notify(&self.observers, stage, &oldest);
+                self.dropped_events += count as u64;
+                evicted = count > 0;
+            }
+            OverflowPolicy::BlockFlushCaller if !self.events.is_empty() => {
+                // Failed batches are re-queued, the new events are dropped below
+                let batch: Vec<GameEventsIOEvent> = self.events.drain(..).collect();
+                let _ = self.send_batch(batch, None);
+            }
+            OverflowPolicy::BlockFlushCaller => {}
+            OverflowPolicy::DropNewest => {}
+        }
+
+        // Whatever still doesn't fit is dropped from the incoming events
+        let excess = overflow(self.events.len(), incoming.len()).min(incoming.len());
+        let dropped = match self.overflow_policy {
+            OverflowPolicy::DropOldest => 0..excess,
+            _ => incoming.len() - excess..incoming.len(),
+        };
+        let stage = EventStage::Dropped(DropReason::BufferFull);
+        observer::notify(&self.observers, stage, incoming.drain(dropped).as_slice());
+        self.dropped_events += excess as u64;
+        evicted
+    }
+
+    /// Get the number of events discarded because the buffer was full
+    pub fn dropped_events_count(&self) -> u64 {
+        self.dropped_events
+    }
+
+    /// Keep only `rate` (0.0 to 1.0) of the events named `event_name`
+    ///
+    /// Events are picked independently; see `Sampler` for per-session sampling.
+    /// Critical events (purchases, crashes, session summaries) are always kept.
+    pub fn set_sampling(&mut self, event_name: impl Into<String>, rate: f64) {
+        let sampler = self.sampler.take().unwrap_or_default();
+        self.sampler = Some(sampler.event(event_name, SamplingRule::per_event(rate)));
+    }
+
+    /// Get the number of events dropped by sampling, per event name
+    pub fn sampled_out_events(&self) -> &HashMap<String, u64> {
+        &self.sampled_out
+    }
+
+    /// Log at most `events_per_minute` events per minute, in bursts of up to a minute's worth
+    ///
+    /// Critical events (purchases, crashes) are never rate limited.
+    pub fn set_rate_limit(&mut self, events_per_minute: u32) {
+        self.rate_limiter = Some(RateLimiter::per_minute(events_per_minute));
+    }
+
+    /// Remove the rate limit
+    pub fn clear_rate_limit(&mut self) {
+        self.rate_limiter = None;
+    }
+
+    /// Get the number of events dropped by the rate limiter
+    pub fn rate_limited_events_count(&self) -> u64 {
+        self.rate_limited
+    }
+
+    /// Log a purchase, ignoring repeats of the same store transaction id
+    ///
+    /// Ids are remembered for `purchase_dedupe_window` (persisted in
+    /// `storage_dir` if set), and the event gets a `transaction_id` property.
+    /// Returns whether the event was buffered; the id is only remembered if
+    /// it was, so the store's retry of a dropped purchase is logged. Failed
+    /// validation is returned as an error, a persistence error is returned
+    /// after the event has been buffered.
+    pub fn log_purchase(
+        &mut self,
+        transaction_id: impl Into<String>,
+        mut event: GameEventsIOEvent,
+    ) -> Result<bool, GameEventsIOError> {
+        if self.opted_out {
+            return Ok(false);
+        }
+
+        let transaction_id = transaction_id.into();
+        let now = unix_now();
+        if self
+            .purchase_dedupe
+            .is_duplicate(&transaction_id, now, self.purchase_dedupe_window)
+        {
+            return Ok(false);
+        }
+
+        event.event_properties.insert(
+            purchase::TRANSACTION_ID_PROPERTY.to_string(),
+            serde_json::json!(transaction_id),
+        );
+        if !self.buffer_event(event)? {
+            return Ok(false);
+        }
+        self.purchase_dedupe.record(transaction_id, now);
+        self.purchase_dedupe.save()?;
+        Ok(true)
+    }
+
+    /// Log a purchase with its store receipt for server-side verification
+    ///
+    /// Deduplicated like `log_purchase`.
+    pub fn log_verified_purchase(
+        &mut self,
+        transaction_id: impl Into<String>,
+        receipt: PurchaseReceipt,
+        mut event: GameEventsIOEvent,
+    ) -> Result<bool, GameEventsIOError> {
+        event.receipt = Some(receipt);
+        self.log_purchase(transaction_id, event)
+    }
+
+    /// Send all buffered events to the backend
+    pub fn flush(&mut self) -> Result<FlushResult, GameEventsIOError> {
+        self.settle_storms();
+        if self.events.is_empty() {
+            return Ok(FlushResult::default());
+        }
+
+        let events_to_send: Vec<GameEventsIOEvent> = self.events.drain(..).collect();
+
+        self.send_batch(events_to_send, None)
+    }
+
+    /// Send events in batches (useful for large event counts)
+    pub fn flush_batch(&mut self, batch_size: usize) -> Result<FlushResult, GameEventsIOError> {
+        self.settle_storms();
+        if self.events.is_empty() {
+            return Ok(FlushResult::default());
+        }
+
+        let events_to_send: Vec<GameEventsIOEvent> = if self.events.len() > batch_size {
+            self.events.drain(..batch_size).collect()
+        } else {
+            self.events.drain(..).collect()
+        };
+
+        self.send_batch(events_to_send, None)
+    }
+
+    /// Send the whole buffer in batches of `batch_size`, returning the result of each batch
+    ///
+    /// Stops at the first batch that fails: its error is the last result,
+    /// and the events not sent yet stay buffered.
+    pub fn flush_all(&mut self, batch_size: usize) -> Vec<Result<FlushResult, GameEventsIOError>> {
+        self.settle_storms();
+        let mut results = Vec::new();
+        while !self.events.is_empty() {
+            let result = self.flush_batch(batch_size.max(1));
+            let failed = result.is_err();
+            results.push(result);
+            if failed {
+                break;
+            }
+        }
+        results
+    }
+
+    /// Send buffered events of at least `min_priority`, keeping the others buffered
+    pub fn flush_priority(
+        &mut self,
+        min_priority: EventPriority,
+    ) -> Result<FlushResult, GameEventsIOError> {
+        self.settle_storms();
+        let (events_to_send, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.events)
+            .into_iter()
+            .partition(|event| event.priority() >= min_priority);
+        self.events = rest;
+
+        if events_to_send.is_empty() {
+            return Ok(FlushResult::default());
+        }
+
+        self.send_batch(events_to_send, None)
+    }
+
+    /// Send as many buffered events as possible before the game exits
+    ///
+    /// Critical events (purchases, crashes) go first, then normal, then low
+    /// priority events, in batches until `timeout` runs out. Failed requests
+    /// are not retried and every request gives up at the deadline, so this
+    /// returns in about `timeout` even offline. Whatever remains stays
+    /// buffered, and is kept on disk in priority order if `persist_to` is
+    /// configured.
+    pub fn shutdown(&mut self, timeout: Duration) -> Result<FlushResult, GameEventsIOError> {
+        let deadline = Instant::now() + timeout;
+        self.settle_storms();
+        // Stable, so events of the same priority keep their order
+        self.events
+            .sort_by_key(|event| std::cmp::Reverse(event.priority()));
+
+        let mut total = FlushResult::default();
+        let mut outcome = Ok(());
+        while !self.events.is_empty() && Instant::now() < deadline {
+            let count = self.events.len().min(SHUTDOWN_BATCH_SIZE);
+            let batch: Vec<GameEventsIOEvent> = self.events.drain(..count).collect();
+            match self.send_batch(batch, Some(deadline)) {
+                Ok(result) => {
+                    total.accepted += result.accepted;
+                    total.rejected += result.rejected;
+                    total.body = result.body;
+                }
+                Err(e) => {
+                    outcome = Err(e);
+                    break;
+                }
+            }
+        }
+
+        self.sync_queue();
+        outcome.map(|()| total)
+    }
+
+    /// Send only the buffered events with `tag`, keeping the others buffered
+    pub fn flush_only(&mut self, tag: &str) -> Result<FlushResult, GameEventsIOError> {
+        self.settle_storms();
+        let (events_to_send, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.events)
+            .into_iter()
+            .partition(|event| event.has_tag(tag));
+        self.events = rest;
+
+        if events_to_send.is_empty() {
+            return Ok(FlushResult::default());
+        }
+
+        self.send_batch(events_to_send, None)
+    }
+
+    /// Discard buffered events with `tag`, returning how many were removed
+    pub fn drop_tagged(&mut self, tag: &str) -> usize {
+        let (dropped, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.events)
+            .into_iter()
+            .partition(|event| event.has_tag(tag));
+        self.events = kept;
+        let stage = EventStage::Dropped(DropReason::Discarded);
+        observer::notify(&self.observers, stage, &dropped);
+        let removed = dropped.len();
+        if removed > 0 {
+            self.sync_queue();
+        }
+        removed
+    }
+
+    /// Send one batch, retrying per the retry policy
+    ///
+    /// If every attempt fails, events that may still be accepted later go back
+    /// to the front of the buffer so the next flush picks them up again.
+    ///
+    /// With a `deadline` (during `shutdown`), failed attempts are not retried
+    /// and each request gives up when the deadline passes.
+    fn send_batch(
+        &mut self,
+        batch: Vec<GameEventsIOEvent>,
+        deadline: Option<Instant>,
+    ) -> Result<FlushResult, GameEventsIOError> {
+        if self.paused {
+            self.events.splice(0..0, batch);
+            return Err(GameEventsIOError::Unauthorized);
+        }
+
+        self.deliver(batch, deadline)
+    }
+
+    /// Send a batch and sort out the events of a failed one
+    ///
+    /// - retryable errors (5xx, timeouts, 429) put the batch back in the buffer
+    /// - 413 splits the batch in halves and sends them one after the other
+    /// - 401/403 put the batch back and pause uploads until `resume_uploads`
+    /// - other 4xx move the refused events to the dead letters
+    fn deliver(
+        &mut self,
+        mut batch: Vec<GameEventsIOEvent>,
+        deadline: Option<Instant>,
+    ) -> Result<FlushResult, GameEventsIOError> {
+        let mut attempt = 1;
+        let err = loop {
+            let upload_ms = unix_now_ms();
+            for event in batch.iter_mut() {
+                event.client_upload_time = Some(upload_ms / 1000);
+                event.client_upload_time_ms = Some(upload_ms);
+            }
+
+            // Without clock correction, the batch itself is lent to the transport
+            let corrected = self
+                .clock_correction()
+                .map(|offset| clock::corrected(&batch, offset));
+            let lent = corrected.is_none();
+            let payload = Arc::new(corrected.unwrap_or_else(|| std::mem::take(&mut batch)));
+            let timeout =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            // Not within a shutdown deadline, which a slow mirror could use up
+            if let (Some(mirror), None) = (&self.mirror, deadline) {
+                let _ = mirror.send_shared(Arc::clone(&payload), None);
+            }
+            let response = self.transport.send_shared(Arc::clone(&payload), timeout);
+            if lent {
+                // The transport is done with it, so this doesn't copy the batch
+                batch = Arc::try_unwrap(payload).unwrap_or_else(|payload| payload.to_vec());
+            }
+            if let Ok(TransportResponse {
+                server_time: Some(server_time),
+                ..
+            }) = &response
+            {
+                self.clock_offset_ms = Some(clock::offset_ms(*server_time, upload_ms));
+            }
+
+            let err = match response
+                .and_then(|response| FlushResult::from_response(response, batch.len()))
+            {
+                Ok(result) => {
+                    for (index, event) in batch.iter().enumerate() {
+                        let stage = if result.rejected_indices.contains(&index) {
+                            EventStage::Rejected
+                        } else {
+                            EventStage::Sent
+                        };
+                        observer::notify(&self.observers, stage, [event]);
+                    }
+                    self.sync_queue();
+                    return Ok(result);
+                }
+                Err(err) => err,
+            };
+
+            let delay = match deadline {
+                Some(_) => None,
+                None => self.retry_policy.next_delay(attempt, &err),
+            };
+            match delay {
+                Some(delay) => thread::sleep(delay),
+                None => break err,
+            }
+            attempt += 1;
+        };
+
+        if matches!(err, GameEventsIOError::PayloadTooLarge) && batch.len() > 1 {
+            return self.deliver_halves(batch, deadline);
+        }
+        let failed = FailedBatch::sort(&err, batch);
+        self.events.splice(0..0, failed.requeue);
+        self.dead_letter(failed.dead_letters);
+        if failed.pause {
+            self.paused = true;
+            diagnostics::report(&err);
+        }
+        self.sync_queue();
+        Err(err)
+    }
+
+    /// Send a batch the backend found too large as two smaller ones
+    fn deliver_halves(
+        &mut self,
+        mut batch: Vec<GameEventsIOEvent>,
+        deadline: Option<Instant>,
+    ) -> Result<FlushResult, GameEventsIOError> {
+        let second = batch.split_off(batch.len() / 2);
+        let (first_len, second_len) = (batch.len(), second.len());
+
+        // The second half waits in the buffer, ready for the next flush if
+        // the first one fails
+        self.events.splice(0..0, second);
+        let first = self.deliver(batch, deadline)?;
+        let second = self.events.drain(0..second_len).collect();
+        let second = self.deliver(second, deadline)?;
+
+        Ok(FlushResult {
+            accepted: first.accepted + second.accepted,
+            rejected: first.rejected + second.rejected,
+            rejected_indices: first
+                .rejected_indices
+                .into_iter()
+                .chain(second.rejected_indices.into_iter().map(|i| i + first_len))
+                .collect(),
+            body: second.body,
+        })
+    }
+
+    /// Keep events the backend refused for good, so they can be inspected
+    ///
+    /// Also appended to `dead_letters.jsonl` in the storage directory, if set.
+    fn dead_letter(&mut self, events: Vec<GameEventsIOEvent>) {
+        if events.is_empty() {
+            return;
+        }
+        observer::notify(&self.observers, EventStage::Rejected, &events);
+        if let Some(dir) = &self.storage_dir {
+            let file = FileTransport::new(dir.join(storage::DEAD_LETTERS_FILE));
+            if let Err(e) = file.send(&events) {
+                diagnostics::report(&e);
+            }
+        }
+        self.dead_letters.extend(events);
+        let excess = self.dead_letters.len().saturating_sub(MAX_DEAD_LETTERS);
+        self.dead_letters.drain(..excess);
+    }
+
+    /// Take the events the backend refused (other 4xx, invalid events)
+    pub fn take_dead_letters(&mut self) -> Vec<GameEventsIOEvent> {
+        std::mem::take(&mut self.dead_letters)
+    }
+
+    /// Whether uploads are paused because the backend rejected the API key
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Send again after uploads were paused by a 401/403
+    pub fn resume_uploads(&mut self) {
+        self.paused = false;
+    }
+
+    /// Write the whole buffer to the offline queue and wait for the disk
+    ///
+    /// Call it from low-memory warnings and console suspend or constrained
+    /// mode callbacks, so the tail of the session survives if the OS kills
+    /// the game. Fails with `ErrorKind::Unsupported` without `persist_to`.
+    pub fn persist_now(&self) -> std::io::Result<()> {
+        match &self.queue {
+            Some(queue) => queue.rewrite_synced(&self.events),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "No offline queue configured (see persist_to)",
+            )),
+        }
+    }
+
+    /// Backend clock minus local clock in milliseconds
+    ///
+    /// Learned from the `Date` header of upload responses; `None` until a
+    /// response reported it.
+    pub fn clock_offset_ms(&self) -> Option<i64> {
+        self.clock_offset_ms
+    }
+
+    /// Offset to apply to uploads, if correction is enabled and needed
+    fn clock_correction(&self) -> Option<i64> {
+        self.clock_offset_ms
+            .filter(|&offset| self.correct_clock_skew && clock::exceeds_tolerance(offset))
+    }
+
+    /// Make the persistent queue match the buffer, if enabled
+    fn sync_queue(&self) {
+        if let Some(queue) = &self.queue {
+            if let Err(e) = queue.rewrite(&self.events) {
+                diagnostics::report(&e.into());
+            }
+        }
+    }
+
+    /// Change how failed batches are retried
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Find buffered events (including ones restored from the offline queue)
+    ///
+    /// Events already sent are not included.
+    pub fn query(&self, query: EventQuery) -> Vec<&GameEventsIOEvent> {
+        query.run(&self.events)
+    }
+
+    /// Get the API key used by this client
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    /// Get the backend URL used by this client
+    pub fn backend_url(&self) -> &str {
+        &self.backend_url
+    }
+
+    /// Get the TLS, proxy and timeout settings of the SDK's HTTP requests
+    pub fn network_config(&self) -> &NetworkConfig {
+        &self.network_config
+    }
+
+    /// Get the compression used by the default HTTP transport
+    ///
+    /// Not used if a custom `compressor` is set.
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Check whether the default HTTP transport sends the dictionary envelope
+    pub fn dictionary_encoding(&self) -> bool {
+        self.dictionary_encoding
+    }
+
+    /// Check whether the default HTTP transport sends batches as NDJSON
+    pub fn ndjson_uploads(&self) -> bool {
+        self.ndjson_uploads
+    }
+
+    /// Get the payload format sent by the default HTTP transport
+    pub fn wire_version(&self) -> WireVersion {
+        self.wire_version
+    }
+
+    /// Measure the SDK's per-frame cost with this client's upload settings
+    ///
+    /// Meant to be run once at startup; takes a few milliseconds.
+    pub fn self_check(&self, budget: &PerfBudget) -> PerfReport {
+        let compressor: &dyn Compressor = match &self.compressor {
+            Some(compressor) => compressor.as_ref(),
+            None => &self.compression,
+        };
+        budget.measure_with(compressor, self.dictionary_encoding)
+    }
+
+    /// Check that analytics work on this machine by sending one ping event
+    ///
+    /// The ping bypasses the buffer and is tagged `self_test`. Nothing is
+    /// sent while the player is opted out.
+    pub fn self_test(&self) -> SelfTestReport {
+        if self.opted_out {
+            return SelfTestReport {
+                error: Some("player opted out of tracking".to_string()),
+                ..SelfTestReport::default()
+            };
+        }
+        health::run(self.transport.as_ref())
+    }
+
+    /// Get the number of buffered events
+    pub fn pending_events_count(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Mark this run as automated (CI bot, soak test, speedrun tool, ...)
+    ///
+    /// Every event logged afterwards is tagged with `is_automated=true` and
+    /// the reason, and handled according to the automation suppression.
+    pub fn mark_automated(&mut self, reason: impl Into<String>) {
+        self.automation_reason = Some(reason.into());
+    }
+
+    /// Stop tagging events as automated
+    pub fn clear_automated(&mut self) {
+        self.automation_reason = None;
+    }
+
+    /// Check whether this run is marked as automated
+    pub fn is_automated(&self) -> bool {
+        self.automation_reason.is_some()
+    }
+
+    /// Choose how events of automated runs are treated
+    pub fn set_automation_suppression(&mut self, suppression: AutomationSuppression) {
+        self.automation_suppression = suppression;
+    }
+
+    /// Opt the player out of tracking
+    ///
+    /// Discards buffered events and locally stored SDK state, and persists
+    /// the choice so logging stays a no-op across restarts until `opt_in()`.
+    pub fn opt_out(&mut self) -> std::io::Result<()> {
+        self.opted_out = true;
+        self.events.clear();
+        self.dead_letters.clear();
+        self.purchase_dedupe.clear();
+        if let Some(queue) = &self.queue {
+            queue.rewrite(&[])?;
+        }
+        if let Some(guard) = self.storm_guard.as_mut() {
+            *guard = StormGuard::new(guard.config());
+        }
+
+        if let Some(dir) = &self.storage_dir {
+            storage::set_flag(dir, storage::OPT_OUT_FILE)?;
+            storage::purge(dir)?;
+        }
+        Ok(())
+    }
+
+    /// Opt the player out of (`true`) or back in to (`false`) tracking
+    pub fn set_opt_out(&mut self, opted_out: bool) -> std::io::Result<()> {
+        if opted_out {
+            self.opt_out()
+        } else {
+            self.opt_in()
+        }
+    }
+
+    /// Ask the backend to delete all data stored about a player
+    ///
+    /// Also drops the player's unsent events. Works while opted out, so a
+    /// player can opt out and then request deletion.
+    pub fn request_user_data_deletion(&mut self, user_id: &str) -> Result<(), GameEventsIOError> {
+        let before = self.events.len();
+        self.events.retain(|event| event.user_id != user_id);
+        self.dead_letters.retain(|event| event.user_id != user_id);
+        if self.events.len() != before {
+            self.sync_queue();
+        }
+
+        privacy::request_deletion(
+            &self.network_config,
+            &self.deletion_url,
+            &self.api_key,
+            user_id,
+        )
+    }
+
+    /// Opt the player back in to tracking
+    pub fn opt_in(&mut self) -> std::io::Result<()> {
+        self.opted_out = false;
+        if let Some(dir) = &self.storage_dir {
+            storage::clear_flag(dir, storage::OPT_OUT_FILE)?;
+        }
+        Ok(())
+    }
+
+    /// Check whether the player has opted out of tracking
+    pub fn is_opted_out(&self) -> bool {
+        self.opted_out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_creation() {
+        let event = GameEventsIOEventBuilder::default()
+            .event("test_event")
+            .user_id("user123")
+            .session_id("session456")
+            .build()
+            .unwrap();
+
+        assert_eq!(event.event, "test_event");
+        assert_eq!(event.user_id, "user123");
+        assert_eq!(event.session_id, "session456");
+        assert!(event.time > 0);
+    }
+
+    #[test]
+    fn test_event_preserves_unknown_fields() {
+        let exported = serde_json::json!({
+            "event": "purchase",
+            "user_id": "user123",
+            "session_id": "session456",
+            "time": 1000,
+            "event_properties": { "price": 9.99 },
+            "insert_id": "abc-123",
+            "server_received_time": 1002,
+        });
+
+        let mut event: GameEventsIOEvent = serde_json::from_value(exported.clone()).unwrap();
+        assert_eq!(event.extra.len(), 2);
+        assert_eq!(event.extra.get("insert_id").unwrap(), "abc-123");
+
+        let reimported = serde_json::to_value(&event).unwrap();
+        assert_eq!(reimported, exported);
+
+        event.event = "purchase_refunded".to_string();
+        let edited = serde_json::to_value(&event).unwrap();
+        assert_eq!(edited["server_received_time"], 1002);
+    }
+
+    #[test]
+    fn test_client_creation() {
+        let client = GameEventsIOClient::new("test_api_key");
+        assert_eq!(client.pending_events_count(), 0);
+    }
+
+    #[test]
+    fn test_event_buffering() {
+        let mut client = GameEventsIOClient::new("test_api_key");
+
+        let event = GameEventsIOEventBuilder::default()
+            .event("test_event")
+            .user_id("user123")
+            .session_id("session456")
+            .build()
+            .unwrap();
+
+        client.log_event(event);
+        assert_eq!(client.pending_events_count(), 1);
+    }
+
+    #[test]
+    fn test_session_creation() {
+        let session = GameEventsIOSession::new("user123", "session456");
+        assert_eq!(session.user_id(), "user123");
+        assert_eq!(session.session_id(), "session456");
+        assert!(session.user_properties().is_empty());
+    }
+
+    #[test]
+    fn test_session_with_user_properties() {
+        let mut user_props = HashMap::new();
+        user_props.insert("platform".to_string(), serde_json::json!("rust"));
+        user_props.insert("version".to_string(), serde_json::json!("1.0"));
+
+        let session = GameEventsIOSessionBuilder::default()
+            .user_id("user123")
+            .session_id("session456")
+            .user_properties(user_props)
+            .build()
+            .unwrap();
+
+        assert_eq!(session.user_properties().len(), 2);
+    }
+
+    #[test]
+    fn test_session_event_creation() {
+        let session = GameEventsIOSession::new("user123", "session456");
+        session.push_event("test_event", HashMap::new());
+
+        // We expect 2 events now: new_session + test_event
+        let events = session.take_events(2);
+        assert_eq!(events.len(), 2);
+        
+        // Check the second event (the one we added)
+        let event = &events[1];
+
+        assert_eq!(event.event, "test_event");
+        assert_eq!(event.user_id, "user123");
+        assert_eq!(event.session_id, "session456");
+    }
+
+    #[test]
+    fn test_session_set_user_property() {
+        let session = GameEventsIOSession::new("user123", "session456");
+        session.set_user_property("platform", serde_json::json!("rust"));
+        session.set_user_property("level", serde_json::json!(10));
+
+        assert_eq!(session.user_properties().len(), 2);
+
+        // Events created after setting properties should include them
+        session.push_event("test_event", HashMap::new());
+
+        // We expect 2 events: new_session (no props) + test_event (with props)
+        let events = session.take_events(2);
+        let event = &events[1];
+
+        assert_eq!(event.user_properties.len(), 2);
+        assert_eq!(event.user_properties.get("platform").unwrap(), "rust");
+    }
+
+    #[test]
+    fn test_session_defaults() {
+        let session = GameEventsIOSession::default();
+        assert!(!session.user_id().is_empty());
+        assert!(!session.session_id().is_empty());
+        // Simple check to see if it looks like a UUID (36 chars)
+        assert_eq!(session.user_id().len(), 36);
+        assert_eq!(session.session_id().len(), 36);
+        
+        // Should have 1 event automatically
+        assert_eq!(session.events.len(), 1);
+        assert_eq!(session.take_events(1)[0].event, "new_session");
+    }
+
+    #[test]
+    fn test_session_id_precedence() {
+        let session = GameEventsIOSession::new("default_user", "default_session");
+
+        let mut props = HashMap::new();
+        props.insert("user_id".to_string(), serde_json::json!("custom_user"));
+        props.insert(
+            "session_id".to_string(),
+            serde_json::json!("custom_session"),
+        );
+
+        session.push_event("test_event", props);
+
+        // We expect 2 events: new_session + test_event
+        let events = session.take_events(2);
+        let event = &events[1];
+
+        assert_eq!(event.user_id, "custom_user");
+        assert_eq!(event.session_id, "custom_session");
+    }
+
+    #[test]
+    fn test_new_session_event_auto_added() {
+        let session = GameEventsIOSession::new("user123", "session456");
+        
+        // Should have 1 event automatically
+        assert_eq!(session.events.len(), 1);
+        
+        let events = session.take_events(1);
+        let event = &events[0];
+        assert_eq!(event.event, "new_session");
+        assert_eq!(event.session_id, "session456");
+        assert_eq!(event.event_properties.get("session_id").unwrap(), "session456");
+    }
+
+    #[test]
+    fn test_shared_session_pushes_from_threads() {
+        let session = Arc::new(GameEventsIOSession::new("user123", "session456"));
+        let systems: Vec<_> = (0..4)
+            .map(|system| {
+                let session = Arc::clone(&session);
+                thread::spawn(move || {
+                    for _ in 0..250 {
+                        session.push_event(format!("system_{}", system), HashMap::new());
+                    }
+                })
+            })
+            .collect();
+        for system in systems {
+            system.join().unwrap();
+        }
+
+        assert_eq!(session.pending_events_count(), 1001);
+        let events = session.take_events(usize::MAX);
+        assert_eq!(events[0].event, EVENT_NEW_SESSION);
+        assert!(events[1..]
+            .iter()
+            .all(|event| event.session_id == "session456"));
+        let system_2 = events.iter().filter(|e| e.event == "system_2").count();
+        assert_eq!(system_2, 250);
+    }
+
+    #[test]
+    fn test_session_rotates_after_inactivity() {
+        let mut session = GameEventsIOSessionBuilder::default()
+            .session_id("session456")
+            .inactivity_timeout(Duration::from_secs(30 * 60))
+            .build()
+            .unwrap();
+        session.push_event("level_started", HashMap::new());
+        assert_eq!(session.events.len(), 2);
+
+        // Back from an hour in background; the idle time isn't counted
+        let now = unix_now();
+        *session.started_at.get_mut() = now - 3700;
+        *session.last_activity.get_mut() = now - 3600;
+        session.push_event("level_completed", HashMap::new());
+        let events = session.take_events(usize::MAX);
+        let names: Vec<&str> = events.iter().map(|event| event.event.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "new_session",
+                "level_started",
+                EVENT_SESSION_END,
+                EVENT_NEW_SESSION,
+                "level_completed"
+            ]
+        );
+        assert_eq!(events[2].session_id, "session456");
+        assert_eq!(events[2].event_properties[SESSION_DURATION_PROPERTY], 100);
+        assert_ne!(events[4].session_id, "session456");
+        assert_eq!(events[4].session_id, session.session_id());
+
+        // An explicit end starts a new session with the next event
+        session.end_session();
+        session.end_session();
+        assert!(session.check_session());
+        let events = session.take_events(usize::MAX);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event, EVENT_SESSION_END);
+        assert_eq!(events[1].event, EVENT_NEW_SESSION);
+        assert!(!session.check_session());
+    }
+
+    #[test]
+    fn test_session_micro_events() {
+        let session = GameEventsIOSession::new("user123", "session456");
+        session.reserve_micro_events(16);
+        session.push_micro_event(MicroEvent::new("projectile_hit").with("damage", 12));
+
+        // new_session + the converted micro event
+        let events = session.take_events(10);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].event, "projectile_hit");
+        assert_eq!(events[1].session_id, "session456");
+        assert_eq!(events[1].event_properties.get("damage").unwrap(), 12);
+
+        // Micro events keep their order and stay in the session they were pushed in
+        session.push_micro_event(MicroEvent::new("projectile_fired"));
+        session.push_event("level_completed", HashMap::new());
+        session.push_micro_event(MicroEvent::new("projectile_hit"));
+        session.rotate_session();
+        let events = session.take_events(10);
+        let names: Vec<&str> = events.iter().map(|event| event.event.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "projectile_fired",
+                "level_completed",
+                "projectile_hit",
+                EVENT_SESSION_END,
+                EVENT_NEW_SESSION
+            ]
+        );
+        assert_eq!(events[2].session_id, "session456");
+    }
+
+    #[test]
+    fn test_client_storm_guard() {
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .storm_guard(StormGuardConfig::new(2, 1))
+            .build()
+            .unwrap();
+
+        for _ in 0..10 {
+            let event = GameEventsIOEventBuilder::default()
+                .event("frame_hitch")
+                .user_id("user123")
+                .session_id("session456")
+                .time(1000u64)
+                .build()
+                .unwrap();
+            client.log_event(event);
+        }
+
+        // Only events up to the limit are buffered while storming
+        assert_eq!(client.pending_events_count(), 2);
+
+        // The storm has long subsided by now, so a summary gets buffered
+        client.settle_storms();
+        assert_eq!(client.pending_events_count(), 3);
+        assert_eq!(client.events[2].event, storm::EVENT_STORM_DETECTED);
+    }
+
+    #[derive(Default)]
+    struct RecordingTransport {
+        batches: std::sync::Mutex<Vec<Vec<GameEventsIOEvent>>>,
+    }
+
+    impl RecordingTransport {
+        fn batch_sizes(&self) -> Vec<usize> {
+            self.batches.lock().unwrap().iter().map(Vec::len).collect()
+        }
+    }
+
+    impl Transport for RecordingTransport {
+        fn send(
+            &self,
+            batch: &[GameEventsIOEvent],
+        ) -> Result<TransportResponse, GameEventsIOError> {
+            self.batches.lock().unwrap().push(batch.to_vec());
+            Ok(TransportResponse {
+                status: 200,
+                body: "ok".to_string(),
+                retry_after: None,
+                server_time: None,
+            })
+        }
+    }
+
+    /// Answers 503 to the first `failures` requests
+    struct FlakyTransport {
+        failures: std::sync::atomic::AtomicUsize,
+        inner: RecordingTransport,
+    }
+
+    impl Transport for FlakyTransport {
+        fn send(
+            &self,
+            batch: &[GameEventsIOEvent],
+        ) -> Result<TransportResponse, GameEventsIOError> {
+            use std::sync::atomic::Ordering;
+            if self.failures.load(Ordering::SeqCst) > 0 {
+                self.failures.fetch_sub(1, Ordering::SeqCst);
+                return Ok(TransportResponse {
+                    status: 503,
+                    body: String::new(),
+                    retry_after: None,
+                    server_time: None,
+                });
+            }
+            self.inner.send(batch)
+        }
+    }
+
+    /// Answers with the status `status_for` picks for each batch
+    struct StatusTransport<F> {
+        status_for: F,
+        sent: std::sync::Mutex<Vec<usize>>,
+    }
+
+    impl<F: Fn(&[GameEventsIOEvent]) -> u16 + Send + Sync> Transport for StatusTransport<F> {
+        fn send(
+            &self,
+            batch: &[GameEventsIOEvent],
+        ) -> Result<TransportResponse, GameEventsIOError> {
+            let status = (self.status_for)(batch);
+            if status == 200 {
+                self.sent.lock().unwrap().push(batch.len());
+            }
+            Ok(TransportResponse {
+                status,
+                ..TransportResponse::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_failed_batches_are_classified() {
+        let unauthorized = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let transport = Arc::new(StatusTransport {
+            status_for: {
+                let unauthorized = unauthorized.clone();
+                move |batch: &[GameEventsIOEvent]| {
+                    if unauthorized.load(std::sync::atomic::Ordering::SeqCst) {
+                        401
+                    } else if batch.len() > 2 {
+                        413
+                    } else if batch.iter().any(|event| event.event == "bad") {
+                        400
+                    } else {
+                        200
+                    }
+                }
+            },
+            sent: std::sync::Mutex::new(Vec::new()),
+        });
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let log = |client: &mut GameEventsIOClient, names: &[&str]| {
+            for name in names {
+                let event = GameEventsIOEventBuilder::default()
+                    .event(*name)
+                    .user_id("user123")
+                    .session_id("session456")
+                    .build()
+                    .unwrap();
+                client.log_event(event);
+            }
+        };
+
+        // 413 splits the batch until the halves fit
+        log(&mut client, &["a", "b", "c", "d", "e"]);
+        assert_eq!(client.flush().unwrap().accepted, 5);
+        assert_eq!(*transport.sent.lock().unwrap(), [2, 1, 2]);
+
+        // Other 4xx dead-letter the batch
+        log(&mut client, &["bad", "f"]);
+        assert!(matches!(
+            client.flush(),
+            Err(GameEventsIOError::Server { status: 400, .. })
+        ));
+        assert_eq!(client.pending_events_count(), 0);
+        assert_eq!(client.take_dead_letters().len(), 2);
+
+        // 401 pauses uploads, keeping the events, until resumed
+        unauthorized.store(true, std::sync::atomic::Ordering::SeqCst);
+        log(&mut client, &["g"]);
+        assert!(matches!(
+            client.flush(),
+            Err(GameEventsIOError::Unauthorized)
+        ));
+        assert!(client.is_paused());
+        unauthorized.store(false, std::sync::atomic::Ordering::SeqCst);
+        assert!(client.flush().is_err());
+        assert_eq!(client.pending_events_count(), 1);
+        client.resume_uploads();
+        assert_eq!(client.flush().unwrap().accepted, 1);
+    }
 
     #[test]
-    fn test_session_creation() {
-        let session = GameEventsIOSession::new("user123", "session456");
-        assert_eq!(session.user_id(), "user123");
-        assert_eq!(session.session_id(), "session456");
-        assert!(session.user_properties().is_empty());
+    fn test_retry_and_requeue() {
+        let transport = Arc::new(FlakyTransport {
+            failures: 3.into(),
+            inner: RecordingTransport::default(),
+        });
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .retry_policy(RetryPolicy {
+                max_attempts: 2,
+                initial_backoff: Duration::ZERO,
+                ..RetryPolicy::default()
+            })
+            .build()
+            .unwrap();
+
+        for name in ["first", "second"] {
+            let event = GameEventsIOEventBuilder::default()
+                .event(name)
+                .user_id("user123")
+                .session_id("session456")
+                .build()
+                .unwrap();
+            client.log_event(event);
+        }
+
+        // Both attempts fail: the batch goes back into the buffer
+        assert!(client.flush_batch(1).is_err());
+        assert_eq!(client.pending_events_count(), 2);
+
+        // Third request fails, the retry succeeds, order is preserved
+        assert_eq!(client.flush_batch(1).unwrap().accepted, 1);
+        assert_eq!(client.flush().unwrap().accepted, 1);
+        let batches = transport.inner.batches.lock().unwrap();
+        assert_eq!(batches[0][0].event, "first");
+        assert_eq!(batches[1][0].event, "second");
     }
 
     #[test]
-    fn test_session_with_user_properties() {
-        let mut user_props = HashMap::new();
-        user_props.insert("platform".to_string(), serde_json::json!("rust"));
-        user_props.insert("version".to_string(), serde_json::json!("1.0"));
+    fn test_custom_transport() {
+        let transport = Arc::new(RecordingTransport::default());
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
 
-        let session = GameEventsIOSessionBuilder::default()
+        for _ in 0..3 {
+            let event = GameEventsIOEventBuilder::default()
+                .event("test_event")
+                .user_id("user123")
+                .session_id("session456")
+                .build()
+                .unwrap();
+            client.log_event(event);
+        }
+
+        assert_eq!(client.flush_batch(2).unwrap().body, "ok");
+        assert_eq!(client.flush().unwrap().accepted, 1);
+        assert_eq!(transport.batch_sizes(), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_upload_time_stamped_on_send() {
+        let transport = Arc::new(RecordingTransport::default());
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+
+        let event = GameEventsIOEventBuilder::default()
+            .event("test_event")
             .user_id("user123")
             .session_id("session456")
-            .user_properties(user_props)
+            .time(1000u64)
             .build()
             .unwrap();
-
-        assert_eq!(session.user_properties().len(), 2);
+        assert_eq!(event.client_upload_time, None);
+        client.log_event(event);
+        client.flush().unwrap();
+
+        let sent = &transport.batches.lock().unwrap()[0][0];
+        assert_eq!(sent.time, 1000);
+        assert!(sent.client_upload_time.unwrap() > 1000);
+        assert_eq!(
+            sent.client_upload_time_ms.unwrap() / 1000,
+            sent.client_upload_time.unwrap()
+        );
     }
 
     #[test]
-    fn test_session_event_creation() {
-        let mut session = GameEventsIOSession::new("user123", "session456");
-        session.push_event("test_event", HashMap::new());
+    #[cfg(debug_assertions)]
+    fn test_mirror_receives_batches() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mirror_url = format!("http://{}", listener.local_addr().unwrap());
+        let inspector = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            while !String::from_utf8_lossy(&request).contains("mirrored_event") {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        let transport = Arc::new(RecordingTransport::default());
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .mirror_to(mirror_url)
+            .build()
+            .unwrap();
 
-        // We expect 2 events now: new_session + test_event
-        let events = session.take_events(2);
-        assert_eq!(events.len(), 2);
-        
-        // Check the second event (the one we added)
-        let event = &events[1];
+        let event = GameEventsIOEventBuilder::default()
+            .event("mirrored_event")
+            .user_id("user123")
+            .session_id("session456")
+            .build()
+            .unwrap();
+        client.log_event(event);
+        client.flush().unwrap();
 
-        assert_eq!(event.event, "test_event");
-        assert_eq!(event.user_id, "user123");
-        assert_eq!(event.session_id, "session456");
+        let request = inspector.join().unwrap();
+        assert!(!request.contains("test_api_key"));
+        assert!(request.contains("client_upload_time"));
+        assert_eq!(transport.batch_sizes(), vec![1]);
     }
 
     #[test]
-    fn test_session_set_user_property() {
-        let mut session = GameEventsIOSession::new("user123", "session456");
-        session.set_user_property("platform", serde_json::json!("rust"));
-        session.set_user_property("level", serde_json::json!(10));
+    fn test_mark_automated() {
+        let mut client = GameEventsIOClient::new("test_api_key");
+        let event = GameEventsIOEventBuilder::default()
+            .event("test_event")
+            .user_id("user123")
+            .session_id("session456")
+            .build()
+            .unwrap();
 
-        assert_eq!(session.user_properties().len(), 2);
+        client.mark_automated("soak_test");
+        client.log_event(event.clone());
+        let tagged = &client.events[0].event_properties;
+        assert_eq!(tagged.get("is_automated").unwrap(), true);
+        assert_eq!(tagged.get("automation_reason").unwrap(), "soak_test");
+        assert!(tagged.get("exclude_from_reports").is_none());
+
+        client.set_automation_suppression(AutomationSuppression::ExcludeFromReports);
+        client.log_event(event.clone());
+        assert_eq!(
+            client.events[1]
+                .event_properties
+                .get("exclude_from_reports")
+                .unwrap(),
+            true
+        );
 
-        // Events created after setting properties should include them
-        session.push_event("test_event", HashMap::new());
+        client.set_automation_suppression(AutomationSuppression::DropLocally);
+        client.log_event(event.clone());
+        assert_eq!(client.pending_events_count(), 2);
 
-        // We expect 2 events: new_session (no props) + test_event (with props)
-        let events = session.take_events(2);
-        let event = &events[1];
+        client.clear_automated();
+        client.log_event(event);
+        assert!(client.events[2].event_properties.is_empty());
+    }
 
-        assert_eq!(event.user_properties.len(), 2);
-        assert_eq!(event.user_properties.get("platform").unwrap(), "rust");
+    #[test]
+    fn test_flush_priority_keeps_lower_priorities() {
+        let transport = Arc::new(RecordingTransport::default());
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+
+        for name in ["level_started", "purchase", "frame_time"] {
+            let mut builder = GameEventsIOEventBuilder::default();
+            builder
+                .event(name)
+                .user_id("user123")
+                .session_id("session456");
+            if name == "frame_time" {
+                builder.priority(EventPriority::Low);
+            }
+            client.log_event(builder.build().unwrap());
+        }
+
+        client.flush_priority(EventPriority::Critical).unwrap();
+        assert_eq!(transport.batches.lock().unwrap()[0][0].event, "purchase");
+        assert_eq!(client.pending_events_count(), 2);
+
+        client.flush_priority(EventPriority::Normal).unwrap();
+        assert_eq!(
+            transport.batches.lock().unwrap()[1][0].event,
+            "level_started"
+        );
+        assert_eq!(client.events[0].priority(), EventPriority::Low);
     }
 
     #[test]
-    fn test_session_defaults() {
-        let session = GameEventsIOSession::default();
-        assert!(!session.user_id().is_empty());
-        assert!(!session.session_id().is_empty());
-        // Simple check to see if it looks like a UUID (36 chars)
-        assert_eq!(session.user_id().len(), 36);
-        assert_eq!(session.session_id().len(), 36);
-        
-        // Should have 1 event automatically
-        assert_eq!(session.events.len(), 1);
-        assert_eq!(session.events[0].event, "new_session");
+    fn test_flush_all_reports_each_batch() {
+        let transport = Arc::new(RecordingTransport::default());
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+
+        for i in 0..25 {
+            let event = GameEventsIOEventBuilder::default()
+                .event(format!("event_{}", i))
+                .build()
+                .unwrap();
+            client.log_event(event);
+        }
+
+        let results = client.flush_all(10);
+        let accepted: Vec<_> = results
+            .iter()
+            .map(|r| r.as_ref().unwrap().accepted)
+            .collect();
+        assert_eq!(accepted, [10, 10, 5]);
+        assert_eq!(client.pending_events_count(), 0);
+        assert_eq!(transport.batches.lock().unwrap().len(), 3);
+        assert!(client.flush_all(10).is_empty());
     }
 
     #[test]
-    fn test_session_id_precedence() {
-        let mut session = GameEventsIOSession::new("default_user", "default_session");
+    fn test_shutdown_sends_critical_first() {
+        let transport = Arc::new(RecordingTransport::default());
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
 
-        let mut props = HashMap::new();
-        props.insert("user_id".to_string(), serde_json::json!("custom_user"));
-        props.insert(
-            "session_id".to_string(),
-            serde_json::json!("custom_session"),
+        for name in ["frame_time", "level_started", "purchase", "game_crash"] {
+            let mut builder = GameEventsIOEventBuilder::default();
+            builder.event(name);
+            if name == "frame_time" {
+                builder.priority(EventPriority::Low);
+            }
+            client.log_event(builder.build().unwrap());
+        }
+
+        // Out of time: nothing is sent, the buffer is left in priority order
+        client.shutdown(Duration::ZERO).unwrap();
+        assert!(transport.batch_sizes().is_empty());
+        let order: Vec<&str> = client.events.iter().map(|e| e.event.as_str()).collect();
+        assert_eq!(
+            order,
+            ["purchase", "game_crash", "level_started", "frame_time"]
         );
 
-        session.push_event("test_event", props);
+        let result = client.shutdown(Duration::from_secs(60)).unwrap();
+        assert_eq!(result.accepted, 4);
+        assert_eq!(transport.batches.lock().unwrap()[0][3].event, "frame_time");
+
+        // Failed requests aren't retried past the deadline
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(FlakyTransport {
+                failures: 10.into(),
+                inner: RecordingTransport::default(),
+            })
+            .retry_policy(RetryPolicy {
+                max_attempts: 5,
+                initial_backoff: Duration::from_secs(5),
+                max_backoff: Duration::from_secs(30),
+                jitter: 0.0,
+            })
+            .build()
+            .unwrap();
+        client.log_event(GameEventsIOEventBuilder::default().build().unwrap());
+        let started = Instant::now();
+        assert!(client.shutdown(Duration::from_millis(100)).is_err());
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(client.pending_events_count(), 1);
+    }
 
-        // We expect 2 events: new_session + test_event
-        let events = session.take_events(2);
-        let event = &events[1];
+    #[test]
+    fn test_tag_selection() {
+        let transport = Arc::new(MemoryTransport::new());
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(TagFilter::only(transport.clone(), "qa"))
+            .build()
+            .unwrap();
 
-        assert_eq!(event.user_id, "custom_user");
-        assert_eq!(event.session_id, "custom_session");
+        for (name, tags) in [
+            ("level_started", &["qa"][..]),
+            ("frame_time", &["perf"][..]),
+            ("level_completed", &["qa", "perf"][..]),
+            ("app_start", &[][..]),
+        ] {
+            let mut builder = GameEventsIOEventBuilder::default();
+            builder
+                .event(name)
+                .user_id("user123")
+                .session_id("session456");
+            for tag in tags {
+                builder.tag(*tag);
+            }
+            client.log_event(builder.build().unwrap());
+        }
+
+        assert_eq!(client.flush_only("qa").unwrap().accepted, 2);
+        assert_eq!(client.drop_tagged("perf"), 1);
+        assert_eq!(client.pending_events_count(), 1);
+
+        // The filter keeps untagged events away from the QA transport
+        client.flush().unwrap();
+        let sent: Vec<String> = transport.events().into_iter().map(|e| e.event).collect();
+        assert_eq!(sent, ["level_started", "level_completed"]);
+        assert_eq!(client.pending_events_count(), 0);
     }
 
     #[test]
-    fn test_new_session_event_auto_added() {
-        let session = GameEventsIOSession::new("user123", "session456");
-        
-        // Should have 1 event automatically
-        assert_eq!(session.events.len(), 1);
-        
-        let event = &session.events[0];
-        assert_eq!(event.event, "new_session");
-        assert_eq!(event.session_id, "session456");
-        assert_eq!(event.event_properties.get("session_id").unwrap(), "session456");
+    fn test_schema_registry_rejects_at_log_time() {
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(MemoryTransport::new())
+            .schema_registry(SchemaRegistry::new().schema(
+                "purchase",
+                EventSchema::new().require("price", PropertyType::Number),
+            ))
+            .build()
+            .unwrap();
+
+        let purchase = GameEventsIOEventBuilder::default()
+            .event("purchase")
+            .user_id("user123")
+            .session_id("session456")
+            .build()
+            .unwrap();
+        match client.try_log_event(purchase.clone()) {
+            Err(GameEventsIOError::Validation(errors)) => assert_eq!(
+                errors,
+                [ValidationError::MissingProperty("price".to_string())]
+            ),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        client.log_event(purchase);
+        assert_eq!(client.pending_events_count(), 0);
+
+        let purchase = GameEventsIOEvent::purchase("gem_pack", 4.99, "USD")
+            .build()
+            .unwrap();
+        client.try_log_event(purchase).unwrap();
+        assert_eq!(client.pending_events_count(), 1);
+    }
+
+    #[test]
+    fn test_bounded_buffer_policies() {
+        let log = |client: &mut GameEventsIOClient, names: &[&str]| {
+            for name in names {
+                let event = GameEventsIOEventBuilder::default()
+                    .event(*name)
+                    .user_id("user123")
+                    .session_id("session456")
+                    .build()
+                    .unwrap();
+                client.log_event(event);
+            }
+        };
+        let names = |client: &GameEventsIOClient| -> Vec<String> {
+            client
+                .events
+                .iter()
+                .map(|event| event.event.clone())
+                .collect()
+        };
+
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .max_buffered_events(2usize)
+            .build()
+            .unwrap();
+        log(&mut client, &["a", "b", "c"]);
+        assert_eq!(names(&client), vec!["b", "c"]);
+        assert_eq!(client.dropped_events_count(), 1);
+
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .max_buffered_events(2usize)
+            .overflow_policy(OverflowPolicy::DropNewest)
+            .build()
+            .unwrap();
+        log(&mut client, &["a", "b", "c"]);
+        assert_eq!(names(&client), vec!["a", "b"]);
+        assert_eq!(client.dropped_events_count(), 1);
+
+        let transport = Arc::new(RecordingTransport::default());
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .max_buffered_events(2usize)
+            .overflow_policy(OverflowPolicy::BlockFlushCaller)
+            .build()
+            .unwrap();
+        log(&mut client, &["a", "b", "c"]);
+        assert_eq!(transport.batch_sizes(), vec![2]);
+        assert_eq!(names(&client), vec!["c"]);
+        assert_eq!(client.dropped_events_count(), 0);
+    }
+
+    #[test]
+    fn test_query_buffered_events() {
+        let mut client = GameEventsIOClient::new("test_api_key");
+        for (name, time) in [
+            ("offer_shown", 100u64),
+            ("purchase", 200),
+            ("offer_shown", 300),
+        ] {
+            let event = GameEventsIOEventBuilder::default()
+                .event(name)
+                .user_id("user123")
+                .session_id("session456")
+                .time(time)
+                .build()
+                .unwrap();
+            client.log_event(event);
+        }
+
+        let shown = client.query(EventQuery::name("offer_shown").since(200));
+        assert_eq!(shown.len(), 1);
+        assert_eq!(shown[0].time, 300);
+        assert_eq!(client.query(EventQuery::all()).len(), 3);
+    }
+
+    #[test]
+    fn test_client_sampling() {
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .sampler(
+                Sampler::new(SamplingRule::keep_all())
+                    .category("combat_", SamplingRule::per_session(0.0)),
+            )
+            .build()
+            .unwrap();
+
+        for name in ["combat_hit", "level_started"] {
+            let event = GameEventsIOEventBuilder::default()
+                .event(name)
+                .user_id("user123")
+                .session_id("session456")
+                .build()
+                .unwrap();
+            client.log_event(event);
+        }
+        assert_eq!(client.pending_events_count(), 1);
+        assert_eq!(client.events[0].event, "level_started");
+    }
+
+    #[test]
+    fn test_sampling_counts_and_rate_limit() {
+        let mut client = GameEventsIOClient::new("test_api_key");
+        client.set_sampling("frame_hitch", 0.0);
+        client.set_sampling("purchase", 0.0);
+        client.set_rate_limit(2);
+
+        for name in ["frame_hitch", "frame_hitch", "enemy_killed", "enemy_killed"] {
+            let event = GameEventsIOEventBuilder::default()
+                .event(name)
+                .user_id("user123")
+                .session_id("session456")
+                .build()
+                .unwrap();
+            client.log_event(event);
+        }
+        for _ in 0..3 {
+            client.log_event(
+                GameEventsIOEvent::purchase("gem_pack", 4.99, "USD")
+                    .build()
+                    .unwrap(),
+            );
+        }
+        let event = GameEventsIOEventBuilder::default()
+            .event("enemy_killed")
+            .build()
+            .unwrap();
+        client.log_event(event);
+
+        assert_eq!(client.sampled_out_events()["frame_hitch"], 2);
+        assert!(!client.sampled_out_events().contains_key("purchase"));
+        assert_eq!(client.rate_limited_events_count(), 1);
+        assert_eq!(client.pending_events_count(), 5);
+    }
+
+    #[test]
+    fn test_persisted_queue_survives_restart() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", Uuid::new_v4()));
+        let path = dir.join("queue.jsonl");
+
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .persist_to(path.clone())
+            .build()
+            .unwrap();
+        for name in ["first", "second", "third"] {
+            let event = GameEventsIOEventBuilder::default()
+                .event(name)
+                .user_id("user123")
+                .session_id("session456")
+                .build()
+                .unwrap();
+            client.log_event(event);
+        }
+        drop(client);
+
+        // The game crashed offline; the next run sends the leftovers
+        let transport = Arc::new(RecordingTransport::default());
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .persist_to(path.clone())
+            .build()
+            .unwrap();
+        assert_eq!(client.pending_events_count(), 3);
+        client.flush_batch(2).unwrap();
+        assert_eq!(transport.batch_sizes(), vec![2]);
+
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .persist_to(path.clone())
+            .build()
+            .unwrap();
+        assert_eq!(client.pending_events_count(), 1);
+        assert_eq!(client.events[0].event, "third");
+
+        // New events are numbered after the restored ones
+        client.log_event(GameEventsIOEventBuilder::default().build().unwrap());
+        assert_eq!(client.events[0].seq, Some(2));
+        assert_eq!(client.events[1].seq, Some(3));
+
+        // Restored events are subject to the buffer limit too
+        let client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .persist_to(path.clone())
+            .max_buffered_events(1usize)
+            .build()
+            .unwrap();
+        assert_eq!(client.pending_events_count(), 1);
+        assert_eq!(client.events[0].seq, Some(3));
+        assert_eq!(client.dropped_events_count(), 1);
+        let client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .persist_to(path)
+            .build()
+            .unwrap();
+        assert_eq!(client.pending_events_count(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_persist_now_writes_whole_buffer() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", Uuid::new_v4()));
+        let path = dir.join("queue.jsonl");
+        let event = GameEventsIOEventBuilder::default()
+            .event("level_started")
+            .build()
+            .unwrap();
+
+        let client = GameEventsIOClient::new("test_api_key");
+        let err = client.persist_now().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+
+        let shared = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .persist_to(path.clone())
+            .build()
+            .unwrap()
+            .into_shared();
+        shared.log_event(event.clone());
+        shared.log_event(event);
+
+        // The file was lost (e.g. an append failed); persisting restores it
+        std::fs::remove_file(&path).unwrap();
+        shared.persist_now().unwrap();
+        let client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .persist_to(path)
+            .build()
+            .unwrap();
+        assert_eq!(client.pending_events_count(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_log_purchase_ignores_duplicates() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", Uuid::new_v4()));
+        let event = || {
+            GameEventsIOEventBuilder::default()
+                .event("purchase")
+                .user_id("user123")
+                .session_id("session456")
+                .build()
+                .unwrap()
+        };
+
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .storage_dir(dir.clone())
+            .build()
+            .unwrap();
+        assert!(client.log_purchase("order-1", event()).unwrap());
+        assert!(!client.log_purchase("order-1", event()).unwrap());
+        assert!(client.log_purchase("order-2", event()).unwrap());
+        assert_eq!(client.pending_events_count(), 2);
+
+        // A purchase dropped before buffering is logged when the store retries
+        let dropped = AtomicBool::new(false);
+        client.add_interceptor(move |event: &mut GameEventsIOEvent| {
+            event.event_properties[purchase::TRANSACTION_ID_PROPERTY] != "order-3"
+                || dropped.swap(true, Ordering::Relaxed)
+        });
+        assert!(!client.log_purchase("order-3", event()).unwrap());
+        assert!(client.log_purchase("order-3", event()).unwrap());
+        assert_eq!(client.pending_events_count(), 3);
+        assert_eq!(
+            client.events[0]
+                .event_properties
+                .get(purchase::TRANSACTION_ID_PROPERTY)
+                .unwrap(),
+            "order-1"
+        );
+
+        // The store retries the callback after a restart
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .storage_dir(dir.clone())
+            .build()
+            .unwrap();
+        assert!(!client.log_purchase("order-1", event()).unwrap());
+        assert_eq!(client.pending_events_count(), 0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_opt_out_persists() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", Uuid::new_v4()));
+        let build_client = || {
+            GameEventsIOClientBuilder::default()
+                .api_key("test_api_key")
+                .storage_dir(dir.clone())
+                .build()
+                .unwrap()
+        };
+        let event = GameEventsIOEventBuilder::default()
+            .event("test_event")
+            .user_id("user123")
+            .session_id("session456")
+            .build()
+            .unwrap();
+
+        let mut client = build_client();
+        client.log_event(event.clone());
+        client.opt_out().unwrap();
+        assert_eq!(client.pending_events_count(), 0);
+
+        // The opt-out survives a restart
+        let mut client = build_client();
+        assert!(client.is_opted_out());
+        client.log_event(event.clone());
+        assert_eq!(client.pending_events_count(), 0);
+
+        client.opt_in().unwrap();
+        client.log_event(event);
+        assert_eq!(client.pending_events_count(), 1);
+        assert!(!build_client().is_opted_out());
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
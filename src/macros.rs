@@ -0,0 +1,57 @@
+//! Shorthand for logging events from game code.
+
+/// Push an event with inline properties onto a session
+///
+/// Property keys are identifiers and values are anything serializable. The
+/// session adds its user, session id and user properties as usual.
+///
+/// ```no_run
+/// use game_events_sdk::{track, GameEventsIOSession};
+///
+/// let mut session = GameEventsIOSession::default();
+/// let kind = "goblin";
+/// track!(session, "enemy_killed", { enemy_type: kind, dps: 42.5 });
+/// track!(session, "pause_opened");
+/// ```
+#[macro_export]
+macro_rules! track {
+    ($session:expr, $event:expr, { $($key:ident : $value:expr),* $(,)? } $(,)?) => {
+        $session.push_event(
+            $event,
+            ::std::collections::HashMap::from([$((
+                ::std::string::String::from(::std::stringify!($key)),
+                $crate::__serde_json::json!($value),
+            )),*]),
+        )
+    };
+    ($session:expr, $event:expr $(,)?) => {
+        $session.push_event($event, ::std::collections::HashMap::new())
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GameEventsIOSession;
+
+    #[test]
+    fn test_track_macro() {
+        let mut session = GameEventsIOSession::new("user123", "session456");
+        session.set_user_property("platform", serde_json::json!("steam_deck"));
+        session.take_events(10);
+
+        let kind = String::from("goblin");
+        let system = |session: &mut GameEventsIOSession| {
+            track!(session, "enemy_killed", { enemy_type: kind, dps: 42.5, });
+        };
+        system(&mut session);
+        track!(session, "pause_opened");
+
+        let events = session.take_events(10);
+        assert_eq!(events[0].event, "enemy_killed");
+        assert_eq!(events[0].event_properties["enemy_type"], "goblin");
+        assert_eq!(events[0].event_properties["dps"], 42.5);
+        assert_eq!(events[0].user_properties["platform"], "steam_deck");
+        assert_eq!(events[0].session_id, "session456");
+        assert!(events[1].event_properties.is_empty());
+    }
+}
@@ -0,0 +1,203 @@
+//! Allocation-free events for hot paths.
+//!
+//! A `MicroEvent` stores its name and up to `MICRO_EVENT_CAPACITY`
+//! properties inline, using only `'static` strings. It is converted into a
+//! full `GameEventsIOEvent` when events are taken from the session.
+
+use std::collections::HashMap;
+
+use crate::{diagnostics, unix_now, GameEventsIOEvent, GameEventsIOEventBuilder};
+
+/// Maximum number of properties a `MicroEvent` can hold
+pub const MICRO_EVENT_CAPACITY: usize = 8;
+
+/// Property value stored inline in a `MicroEvent`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MicroValue {
+    #[default]
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(&'static str),
+}
+
+impl MicroValue {
+    /// Convert to a JSON value
+    pub fn to_json(self) -> serde_json::Value {
+        match self {
+            MicroValue::Null => serde_json::Value::Null,
+            MicroValue::Bool(v) => serde_json::json!(v),
+            MicroValue::Int(v) => serde_json::json!(v),
+            MicroValue::Float(v) => serde_json::json!(v),
+            MicroValue::Str(v) => serde_json::json!(v),
+        }
+    }
+}
+
+impl From<bool> for MicroValue {
+    fn from(v: bool) -> Self {
+        MicroValue::Bool(v)
+    }
+}
+
+impl From<i32> for MicroValue {
+    fn from(v: i32) -> Self {
+        MicroValue::Int(v.into())
+    }
+}
+
+impl From<i64> for MicroValue {
+    fn from(v: i64) -> Self {
+        MicroValue::Int(v)
+    }
+}
+
+impl From<u32> for MicroValue {
+    fn from(v: u32) -> Self {
+        MicroValue::Int(v.into())
+    }
+}
+
+impl From<f32> for MicroValue {
+    fn from(v: f32) -> Self {
+        MicroValue::Float(v.into())
+    }
+}
+
+impl From<f64> for MicroValue {
+    fn from(v: f64) -> Self {
+        MicroValue::Float(v)
+    }
+}
+
+impl From<&'static str> for MicroValue {
+    fn from(v: &'static str) -> Self {
+        MicroValue::Str(v)
+    }
+}
+
+/// Fixed-size event that never allocates
+#[derive(Clone, Copy, Debug)]
+pub struct MicroEvent {
+    name: &'static str,
+    time: u64,
+    properties: [(&'static str, MicroValue); MICRO_EVENT_CAPACITY],
+    len: usize,
+}
+
+impl MicroEvent {
+    /// Create a micro event stamped with the current time
+    pub fn new(name: &'static str) -> Self {
+        Self::at(name, unix_now())
+    }
+
+    /// Create a micro event with an explicit Unix timestamp in seconds
+    pub fn at(name: &'static str, time: u64) -> Self {
+        Self {
+            name,
+            time,
+            properties: [("", MicroValue::Null); MICRO_EVENT_CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// Add a property, builder style
+    ///
+    /// Properties beyond `MICRO_EVENT_CAPACITY` are dropped.
+    pub fn with(mut self, key: &'static str, value: impl Into<MicroValue>) -> Self {
+        self.push(key, value);
+        self
+    }
+
+    /// Add a property, returning `false` if the event is already full
+    pub fn push(&mut self, key: &'static str, value: impl Into<MicroValue>) -> bool {
+        if self.len == MICRO_EVENT_CAPACITY {
+            return false;
+        }
+        self.properties[self.len] = (key, value.into());
+        self.len += 1;
+        true
+    }
+
+    /// Event name
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Unix timestamp in seconds
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    /// Properties set on this event
+    pub fn properties(&self) -> &[(&'static str, MicroValue)] {
+        &self.properties[..self.len]
+    }
+
+    /// Convert into a full event
+    pub fn into_event(
+        self,
+        user_id: &str,
+        session_id: &str,
+        user_properties: &HashMap<String, serde_json::Value>,
+    ) -> GameEventsIOEvent {
+        let event_properties = self
+            .properties()
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_json()))
+            .collect::<HashMap<_, _>>();
+
+        diagnostics::recover(
+            GameEventsIOEventBuilder::default()
+                .event(self.name)
+                .user_id(user_id)
+                .session_id(session_id)
+                .time(self.time)
+                .event_properties(event_properties)
+                .user_properties(user_properties.clone())
+                .build(),
+            "Failed to build event from micro event",
+            GameEventsIOEvent::default,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_micro_event_properties() {
+        let event = MicroEvent::at("projectile_hit", 42)
+            .with("damage", 12)
+            .with("crit", true)
+            .with("weapon", "bow");
+
+        assert_eq!(event.name(), "projectile_hit");
+        assert_eq!(event.properties().len(), 3);
+        assert_eq!(event.properties()[0], ("damage", MicroValue::Int(12)));
+    }
+
+    #[test]
+    fn test_micro_event_capacity() {
+        let mut event = MicroEvent::at("projectile_hit", 42);
+        for _ in 0..MICRO_EVENT_CAPACITY {
+            assert!(event.push("x", 1.5));
+        }
+        assert!(!event.push("overflow", 1.5));
+        assert_eq!(event.properties().len(), MICRO_EVENT_CAPACITY);
+    }
+
+    #[test]
+    fn test_micro_event_conversion() {
+        let event = MicroEvent::at("projectile_hit", 42)
+            .with("damage", 12)
+            .into_event("user123", "session456", &HashMap::new());
+
+        assert_eq!(event.event, "projectile_hit");
+        assert_eq!(event.user_id, "user123");
+        assert_eq!(event.time, 42);
+        assert_eq!(event.event_properties.get("damage").unwrap(), 12);
+    }
+}
@@ -0,0 +1,110 @@
+//! Newline-delimited JSON uploads.
+//!
+//! With `HttpTransport::ndjson`, batches are sent as one payload per line
+//! instead of a JSON array. Uncompressed uploads are streamed with chunked
+//! transfer encoding: each line is serialized only when the request body is
+//! read, so a batch of tens of thousands of events is never held as one
+//! serialized buffer. Compressed uploads are still serialized line by line,
+//! but into one buffer, since codecs work on whole bodies.
+
+use std::io::{self, Read};
+use std::sync::Arc;
+
+use crate::{GameEventsIOEvent, WireVersion};
+
+/// Content type of NDJSON uploads
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// A batch as NDJSON, in one buffer
+pub(crate) fn encode_batch(
+    batch: &[GameEventsIOEvent],
+    version: WireVersion,
+) -> serde_json::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    for event in batch {
+        version.write_line(event, &mut body)?;
+    }
+    Ok(body)
+}
+
+/// Request body serializing one event per line as it is read
+///
+/// Shares the batch with the client instead of copying it.
+pub(crate) struct NdjsonBody {
+    events: Arc<Vec<GameEventsIOEvent>>,
+    /// Index of the next event to serialize
+    next: usize,
+    version: WireVersion,
+    line: Vec<u8>,
+    /// Bytes of `line` already read
+    pos: usize,
+}
+
+impl NdjsonBody {
+    pub(crate) fn new(events: Arc<Vec<GameEventsIOEvent>>, version: WireVersion) -> Self {
+        Self {
+            events,
+            next: 0,
+            version,
+            line: Vec::new(),
+            pos: 0,
+        }
+    }
+}
+
+impl Read for NdjsonBody {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.line.len() {
+            let Some(event) = self.events.get(self.next) else {
+                return Ok(0);
+            };
+            self.next += 1;
+            self.line.clear();
+            self.pos = 0;
+            self.version.write_line(event, &mut self.line)?;
+        }
+
+        let count = buf.len().min(self.line.len() - self.pos);
+        buf[..count].copy_from_slice(&self.line[self.pos..self.pos + count]);
+        self.pos += count;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameEventsIOEventBuilder, WirePayloadV2};
+
+    #[test]
+    fn test_streamed_body_matches_buffered() {
+        let batch: Vec<_> = (0..100u64)
+            .map(|i| {
+                GameEventsIOEventBuilder::default()
+                    .event(format!("event_{}", i))
+                    .time_ms(1_700_000_000_123 + i)
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+
+        // Read in small chunks, so lines span several reads
+        let batch = Arc::new(batch);
+        let mut body = NdjsonBody::new(Arc::clone(&batch), WireVersion::V2);
+        let mut streamed = Vec::new();
+        let mut chunk = [0; 7];
+        loop {
+            let count = body.read(&mut chunk).unwrap();
+            if count == 0 {
+                break;
+            }
+            streamed.extend_from_slice(&chunk[..count]);
+        }
+        assert_eq!(streamed, encode_batch(&batch, WireVersion::V2).unwrap());
+
+        let lines: Vec<_> = std::str::from_utf8(&streamed).unwrap().lines().collect();
+        assert_eq!(lines.len(), 100);
+        let last: WirePayloadV2 = serde_json::from_str(lines[99]).unwrap();
+        assert_eq!(last.into_event().event, "event_99");
+    }
+}
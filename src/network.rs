@@ -0,0 +1,104 @@
+//! TLS, proxy and timeout settings of the SDK's HTTP requests.
+//!
+//! Certificates are verified by default. `accept_invalid_certs` turns
+//! verification off for local test backends with self-signed certificates
+//! and must never ship in a release build. Extra root certificates let a
+//! studio trust its own CA, e.g. behind a TLS-inspecting corporate proxy.
+
+use std::time::Duration;
+
+use crate::{diagnostics, GameEventsIOError};
+
+/// Settings of the HTTP clients the SDK builds
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NetworkConfig {
+    /// Accept any server certificate, including self-signed and expired ones
+    pub accept_invalid_certs: bool,
+
+    /// Extra trusted root certificates, PEM encoded
+    pub root_certificates: Vec<Vec<u8>>,
+
+    /// Proxy for every request (e.g. "http://proxy.local:3128")
+    pub proxy: Option<String>,
+
+    /// Give up on requests that take longer than this
+    pub timeout: Option<Duration>,
+}
+
+impl NetworkConfig {
+    /// Blocking client with these settings, or the error building it
+    pub(crate) fn try_blocking_client(
+        &self,
+    ) -> Result<reqwest::blocking::Client, GameEventsIOError> {
+        let mut builder = reqwest::blocking::Client::builder()
+            .danger_accept_invalid_certs(self.accept_invalid_certs);
+        for pem in &self.root_certificates {
+            builder = builder.add_root_certificate(reqwest::Certificate::from_pem(pem)?);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Blocking client with these settings, `None` if it could not be built
+    pub(crate) fn blocking_client(&self) -> Option<reqwest::blocking::Client> {
+        diagnostics::recover(
+            self.try_blocking_client().map(Some),
+            "Failed to build HTTP client",
+            || None,
+        )
+    }
+
+    /// Async client with these settings, `None` if it could not be built
+    #[cfg(feature = "async")]
+    pub(crate) fn async_client(&self) -> Option<reqwest::Client> {
+        let client = || -> Result<reqwest::Client, GameEventsIOError> {
+            let mut builder =
+                reqwest::Client::builder().danger_accept_invalid_certs(self.accept_invalid_certs);
+            for pem in &self.root_certificates {
+                builder = builder.add_root_certificate(reqwest::Certificate::from_pem(pem)?);
+            }
+            if let Some(proxy) = &self.proxy {
+                builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+            }
+            if let Some(timeout) = self.timeout {
+                builder = builder.timeout(timeout);
+            }
+            Ok(builder.build()?)
+        };
+        diagnostics::recover(client().map(Some), "Failed to build HTTP client", || None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameEventsIOClientBuilder;
+
+    #[test]
+    fn test_certificates_verified_by_default() {
+        let client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .proxy("http://127.0.0.1:3128")
+            .request_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        let config = client.network_config();
+        assert!(!config.accept_invalid_certs);
+        assert_eq!(config.timeout, Some(Duration::from_secs(5)));
+        assert!(config.try_blocking_client().is_ok());
+
+        let config = NetworkConfig {
+            root_certificates: vec![b"not a certificate".to_vec()],
+            ..NetworkConfig::default()
+        };
+        assert!(matches!(
+            config.try_blocking_client(),
+            Err(GameEventsIOError::Network(_))
+        ));
+    }
+}
@@ -0,0 +1,149 @@
+//! Callbacks following events through the pipeline.
+//!
+//! Observers see every event at each stage it reaches: logged, validated
+//! (and buffered), sent, rejected (by the schema registry or the backend)
+//! or dropped (by an interceptor, sampling, the rate limiter, automation
+//! suppression or a full buffer). They can't change events, which makes
+//! them a good fit for an in-editor inspector showing where each event is.
+//! Observers run synchronously on the logging thread, so keep them cheap.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::GameEventsIOEvent;
+
+/// Stage of the pipeline an event reached
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EventStage {
+    /// Passed to `log_event`, before interceptors and validation
+    Logged,
+    /// Passed interceptors and validation, about to be buffered
+    Validated,
+    /// Accepted by the backend
+    Sent,
+    /// Refused by the schema registry or the backend
+    Rejected,
+    /// Discarded on the client
+    Dropped(DropReason),
+}
+
+/// Why the client discarded an event
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DropReason {
+    /// An interceptor returned `false`
+    Interceptor,
+    /// Not picked by the sampler
+    Sampled,
+    /// Over the rate limit
+    RateLimited,
+    /// Suppressed because the run is automated
+    Automation,
+    /// The buffer was full
+    BufferFull,
+    /// Removed from the buffer by `drop_tagged`
+    Discarded,
+}
+
+/// Receives every event at each stage it reaches
+pub trait EventObserver: Send + Sync {
+    /// Called once per event and stage
+    fn on_event(&self, stage: EventStage, event: &GameEventsIOEvent);
+}
+
+impl<F> EventObserver for F
+where
+    F: Fn(EventStage, &GameEventsIOEvent) + Send + Sync,
+{
+    fn on_event(&self, stage: EventStage, event: &GameEventsIOEvent) {
+        self(stage, event)
+    }
+}
+
+impl fmt::Debug for dyn EventObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EventObserver")
+    }
+}
+
+/// Tell every observer that `events` reached `stage`
+pub(crate) fn notify<'a>(
+    observers: &[Arc<dyn EventObserver>],
+    stage: EventStage,
+    events: impl IntoIterator<Item = &'a GameEventsIOEvent>,
+) {
+    if observers.is_empty() {
+        return;
+    }
+    for event in events {
+        for observer in observers {
+            observer.on_event(stage, event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        GameEventsIOClientBuilder, GameEventsIOEventBuilder, MemoryTransport, OverflowPolicy,
+    };
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_observer_follows_pipeline() {
+        let stages = Arc::new(Mutex::new(Vec::new()));
+        let recorded = stages.clone();
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(MemoryTransport::new())
+            .max_buffered_events(1usize)
+            .overflow_policy(OverflowPolicy::DropNewest)
+            .interceptor(|event: &mut GameEventsIOEvent| !event.event.starts_with("debug_"))
+            .build()
+            .unwrap();
+        client.observe(Box::new(
+            move |stage: EventStage, event: &GameEventsIOEvent| {
+                recorded.lock().unwrap().push((stage, event.event.clone()));
+            },
+        ));
+
+        for name in ["debug_overlay", "level_started", "level_completed"] {
+            let event = GameEventsIOEventBuilder::default()
+                .event(name)
+                .build()
+                .unwrap();
+            client.log_event(event);
+        }
+        client.flush().unwrap();
+
+        let stages = stages.lock().unwrap();
+        let of = |name: &str| -> Vec<EventStage> {
+            stages
+                .iter()
+                .filter(|(_, event)| event == name)
+                .map(|(stage, _)| *stage)
+                .collect()
+        };
+        assert_eq!(
+            of("debug_overlay"),
+            [
+                EventStage::Logged,
+                EventStage::Dropped(DropReason::Interceptor)
+            ]
+        );
+        assert_eq!(
+            of("level_started"),
+            [EventStage::Logged, EventStage::Validated, EventStage::Sent]
+        );
+        assert_eq!(
+            of("level_completed"),
+            [
+                EventStage::Logged,
+                EventStage::Validated,
+                EventStage::Dropped(DropReason::BufferFull)
+            ]
+        );
+    }
+}
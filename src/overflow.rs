@@ -0,0 +1,16 @@
+//! What happens when the event buffer is full.
+
+/// Policy applied when logging would exceed `max_buffered_events`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Evict the oldest buffered events to make room
+    #[default]
+    DropOldest,
+
+    /// Discard the events being logged
+    DropNewest,
+
+    /// Flush on the logging thread to make room, dropping the new events if
+    /// the flush fails
+    BlockFlushCaller,
+}
@@ -0,0 +1,183 @@
+//! Performance budget of the event pipeline.
+//!
+//! `PerfBudget::measure` times the work the SDK does for each event on the
+//! machine it runs on, so a game can check at startup that telemetry fits
+//! its per-frame budget. The `benches/pipeline.rs` criterion suite covers
+//! the same stages in more detail.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::io;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+
+use crate::{
+    dictionary, Compression, Compressor, GameEventsIOEvent, GameEventsIOEventBuilder, WireVersion,
+};
+
+/// Events timed for each stage of a measurement
+const SAMPLES: u32 = 200;
+
+/// Properties inserted into each event while measuring
+const PROPERTIES: [&str; 5] = ["level", "score", "item_id", "duration_ms", "platform"];
+
+/// Time telemetry may take per frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PerfBudget {
+    /// Time available to the SDK each frame (default: 0.1 ms)
+    pub frame_budget: Duration,
+
+    /// Events the game logs in a typical frame (default: 10)
+    pub events_per_frame: u32,
+}
+
+impl Default for PerfBudget {
+    fn default() -> Self {
+        Self {
+            frame_budget: Duration::from_micros(100),
+            events_per_frame: 10,
+        }
+    }
+}
+
+/// Mean cost per event of each pipeline stage, as measured
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PerfReport {
+    /// Building an event with the builder
+    pub event_creation: Duration,
+
+    /// Inserting five event properties
+    pub property_insertion: Duration,
+
+    /// Serializing a single event to JSON
+    pub serialization: Duration,
+
+    /// Encoding the event as part of an upload body (share of the batch)
+    pub batch_assembly: Duration,
+
+    /// Estimated SDK time per frame, see `PerfBudget::events_per_frame`
+    pub per_frame: Duration,
+
+    /// Budget the report was measured against
+    pub frame_budget: Duration,
+}
+
+impl PerfReport {
+    /// Whether the estimated time per frame fits the budget
+    pub fn within_budget(&self) -> bool {
+        self.per_frame <= self.frame_budget
+    }
+}
+
+impl PerfBudget {
+    /// Measure the pipeline with plain JSON uploads
+    ///
+    /// Takes a few milliseconds; run it once, e.g. at startup.
+    pub fn measure(&self) -> PerfReport {
+        self.measure_with(&Compression::None, false)
+    }
+
+    /// Measure the pipeline with the given upload encoding
+    pub(crate) fn measure_with(
+        &self,
+        compressor: &dyn Compressor,
+        dictionary_encoding: bool,
+    ) -> PerfReport {
+        let (mut events, event_creation) = time_each(|i| {
+            GameEventsIOEventBuilder::default()
+                .event("level_completed")
+                .user_id("perf_user")
+                .session_id("perf_session")
+                .time(u64::from(i))
+                .build()
+                .unwrap_or_default()
+        });
+
+        let start = Instant::now();
+        for (i, event) in events.iter_mut().enumerate() {
+            insert_properties(&mut event.event_properties, i);
+        }
+        let property_insertion = start.elapsed() / SAMPLES;
+
+        let start = Instant::now();
+        for event in &events {
+            black_box(serde_json::to_vec(event).ok());
+        }
+        let serialization = start.elapsed() / SAMPLES;
+
+        let start = Instant::now();
+        black_box(assemble_batch(&events, compressor, dictionary_encoding).ok());
+        let batch_assembly = start.elapsed() / SAMPLES;
+
+        let per_event = event_creation + property_insertion + batch_assembly;
+        PerfReport {
+            event_creation,
+            property_insertion,
+            serialization,
+            batch_assembly,
+            per_frame: per_event * self.events_per_frame,
+            frame_budget: self.frame_budget,
+        }
+    }
+}
+
+/// Run `f` `SAMPLES` times, returning the results and the mean time per call
+fn time_each<T>(mut f: impl FnMut(u32) -> T) -> (Vec<T>, Duration) {
+    let mut results = Vec::with_capacity(SAMPLES as usize);
+    let start = Instant::now();
+    for i in 0..SAMPLES {
+        results.push(f(i));
+    }
+    (results, start.elapsed() / SAMPLES)
+}
+
+fn insert_properties(properties: &mut HashMap<String, serde_json::Value>, i: usize) {
+    for (key, value) in PROPERTIES.into_iter().zip([
+        json!(i % 50),
+        json!(i * 10),
+        json!("sword_of_a_thousand_truths"),
+        json!(16),
+        json!("steam_deck"),
+    ]) {
+        properties.insert(key.to_string(), value);
+    }
+}
+
+/// Build an upload body the way `HttpTransport` does
+fn assemble_batch(
+    batch: &[GameEventsIOEvent],
+    compressor: &dyn Compressor,
+    dictionary_encoding: bool,
+) -> io::Result<Vec<u8>> {
+    let body = if dictionary_encoding {
+        serde_json::to_vec(&dictionary::encode_batch(batch)?)?
+    } else {
+        WireVersion::V1.encode_batch(batch)?
+    };
+    compressor.compress(&body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_against_budget() {
+        let generous = PerfBudget {
+            frame_budget: Duration::from_secs(1),
+            ..PerfBudget::default()
+        };
+        let report = generous.measure();
+        assert!(report.per_frame > Duration::ZERO);
+        assert!(report.within_budget());
+
+        let impossible = PerfBudget {
+            frame_budget: Duration::ZERO,
+            ..PerfBudget::default()
+        };
+        assert!(!impossible
+            .measure_with(&Compression::Gzip, true)
+            .within_budget());
+    }
+}
@@ -0,0 +1,66 @@
+//! Power-aware upload profiles.
+//!
+//! On battery or in handheld mode the game can switch the worker to
+//! `PowerProfile::LowPower`, which wakes the radio less often: flushes are
+//! spaced out, batches get bigger, and only critical events are sent until
+//! enough other events have piled up to fill a large batch.
+
+use std::time::Duration;
+
+/// How much longer the flush interval is in low-power mode
+pub const LOW_POWER_INTERVAL_MULTIPLIER: u32 = 4;
+
+/// How much bigger batches are in low-power mode
+pub const LOW_POWER_BATCH_MULTIPLIER: usize = 4;
+
+/// Upload profile of the background worker
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PowerProfile {
+    /// Flush on every interval
+    #[default]
+    Normal,
+
+    /// Minimize radio wake-ups (battery, handheld mode)
+    LowPower,
+}
+
+impl PowerProfile {
+    /// Flush interval for this profile
+    pub fn interval(self, base: Duration) -> Duration {
+        match self {
+            PowerProfile::Normal => base,
+            PowerProfile::LowPower => base * LOW_POWER_INTERVAL_MULTIPLIER,
+        }
+    }
+
+    /// Batch size for this profile
+    pub fn batch_size(self, base: usize) -> usize {
+        match self {
+            PowerProfile::Normal => base,
+            PowerProfile::LowPower => base.saturating_mul(LOW_POWER_BATCH_MULTIPLIER),
+        }
+    }
+
+    /// Whether non-critical events wait until a full batch is available
+    pub fn defers_non_critical(self) -> bool {
+        self == PowerProfile::LowPower
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_low_power_settings() {
+        let base = Duration::from_secs(30);
+        assert_eq!(PowerProfile::Normal.interval(base), base);
+        assert_eq!(
+            PowerProfile::LowPower.interval(base),
+            Duration::from_secs(120)
+        );
+        assert_eq!(PowerProfile::LowPower.batch_size(100), 400);
+        assert!(PowerProfile::LowPower.defers_non_critical());
+        assert!(!PowerProfile::Normal.defers_non_critical());
+    }
+}
@@ -0,0 +1,58 @@
+//! Delivery priority of events.
+
+use serde::{Deserialize, Serialize};
+
+/// How urgently an event needs to reach the backend
+///
+/// Ordered from least to most urgent.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum EventPriority {
+    /// Trivia that can wait or be lost (e.g. per-frame telemetry)
+    Low,
+
+    /// Regular gameplay events
+    #[default]
+    Normal,
+
+    /// Events that must not be lost (purchases, crashes)
+    Critical,
+}
+
+impl EventPriority {
+    /// Default priority for an event name
+    ///
+    /// Purchases and crashes are critical, everything else is normal.
+    pub fn for_event_name(name: &str) -> Self {
+        if name.contains("purchase") || name.contains("crash") {
+            EventPriority::Critical
+        } else {
+            EventPriority::Normal
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_from_name() {
+        assert_eq!(
+            EventPriority::for_event_name("purchase"),
+            EventPriority::Critical
+        );
+        assert_eq!(
+            EventPriority::for_event_name("game_crash"),
+            EventPriority::Critical
+        );
+        assert_eq!(
+            EventPriority::for_event_name("level_started"),
+            EventPriority::Normal
+        );
+        assert!(EventPriority::Critical > EventPriority::Normal);
+        assert!(EventPriority::Normal > EventPriority::Low);
+    }
+}
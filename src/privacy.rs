@@ -0,0 +1,119 @@
+//! Compliance hooks for GDPR/CCPA.
+//!
+//! Opting out is handled by the client (`set_opt_out`). This module holds
+//! the redaction of configured property keys, applied at log time so the
+//! values never reach the buffer, the offline queue or the backend, and the
+//! user data deletion request sent to the backend.
+
+use serde_json::json;
+
+use crate::{FlushResult, GameEventsIOError, GameEventsIOEvent, NetworkConfig, TransportResponse};
+
+/// Default game-events.io endpoint for user data deletion requests
+pub const DEFAULT_DELETION_URL: &str = "https://api.game-events.io/v1/deletion_requests";
+
+/// Remove the given keys from the event and user properties of an event
+pub(crate) fn redact(event: &mut GameEventsIOEvent, keys: &[String]) {
+    for key in keys {
+        event.event_properties.remove(key);
+        event.user_properties.remove(key);
+    }
+}
+
+/// Ask the backend to delete everything it stores about `user_id`
+pub(crate) fn request_deletion(
+    network_config: &NetworkConfig,
+    url: &str,
+    api_key: &str,
+    user_id: &str,
+) -> Result<(), GameEventsIOError> {
+    let client = network_config.try_blocking_client()?;
+    let mut request = client.post(url).json(&json!({ "user_id": user_id }));
+    if !api_key.is_empty() {
+        request = request.header("Authorization", format!("Bearer {}", api_key));
+    }
+    let response = request.send()?;
+    let response = TransportResponse {
+        status: response.status().as_u16(),
+        retry_after: crate::transport::parse_retry_after(response.headers()),
+        server_time: crate::transport::parse_server_time(response.headers()),
+        body: response.text()?,
+    };
+    FlushResult::from_response(response, 0).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameEventsIOClientBuilder, GameEventsIOEventBuilder};
+    use std::collections::HashMap;
+    use std::io::{Read, Write};
+
+    fn event(user_id: &str) -> GameEventsIOEvent {
+        GameEventsIOEventBuilder::default()
+            .event("chat_message")
+            .user_id(user_id)
+            .event_properties(HashMap::from([
+                ("email".to_string(), json!("player@example.com")),
+                ("channel".to_string(), json!("guild")),
+            ]))
+            .user_properties(HashMap::from([("ip".to_string(), json!("10.0.0.1"))]))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_privacy_controls() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let deletion_url = format!(
+            "http://{}/v1/deletion_requests",
+            listener.local_addr().unwrap()
+        );
+        let backend = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            while !String::from_utf8_lossy(&request).contains("user123\"}") {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .redact_property("email")
+            .redact_property("ip")
+            .deletion_url(deletion_url)
+            .build()
+            .unwrap();
+
+        client.log_event(event("user123"));
+        client.log_event(event("user456"));
+        let logged = client.query(crate::EventQuery::all());
+        assert_eq!(
+            logged[0].event_properties.keys().collect::<Vec<_>>(),
+            ["channel"]
+        );
+        assert!(logged[0].user_properties.is_empty());
+
+        client.request_user_data_deletion("user123").unwrap();
+        let request = backend.join().unwrap();
+        assert!(request.starts_with("POST /v1/deletion_requests"));
+        assert!(request.contains("Bearer test_api_key"));
+        assert_eq!(client.pending_events_count(), 1);
+
+        client.set_opt_out(true).unwrap();
+        assert_eq!(client.pending_events_count(), 0);
+        client.log_event(event("user456"));
+        assert_eq!(client.pending_events_count(), 0);
+        client.set_opt_out(false).unwrap();
+        assert!(!client.is_opted_out());
+    }
+}
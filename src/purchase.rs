@@ -0,0 +1,186 @@
+//! Duplicate protection for purchase events.
+//!
+//! Store SDKs often deliver the same purchase callback more than once (app
+//! restarts, receipt refreshes). `GameEventsIOClient::log_purchase` remembers
+//! transaction ids for a while so retried callbacks don't double-count revenue.
+//!
+//! `log_verified_purchase` also attaches the store receipt, which the
+//! backend's fraud check verifies with the store before counting revenue.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+/// Event property holding the store transaction id of a purchase
+pub const TRANSACTION_ID_PROPERTY: &str = "transaction_id";
+
+/// How long transaction ids are remembered by default (7 days)
+pub const DEFAULT_PURCHASE_DEDUPE_WINDOW: Duration = Duration::from_secs(7 * 86_400);
+
+/// Store that processed a purchase
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Store {
+    /// Apple App Store
+    AppStore,
+
+    /// Google Play
+    GooglePlay,
+
+    /// Steam
+    Steam,
+}
+
+/// Proof of purchase sent with the event for server-side verification
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PurchaseReceipt {
+    /// Store that processed the purchase
+    pub store: Store,
+
+    /// Receipt as returned by the store SDK
+    ///
+    /// App Store: the base64 app receipt. Google Play: the purchase JSON.
+    /// Steam: the order id.
+    pub receipt: String,
+
+    /// Receipt signature (Google Play only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl PurchaseReceipt {
+    /// Receipt without a signature
+    pub fn new(store: Store, receipt: impl Into<String>) -> Self {
+        Self {
+            store,
+            receipt: receipt.into(),
+            signature: None,
+        }
+    }
+
+    /// Add the receipt signature
+    pub fn signature(mut self, signature: impl Into<String>) -> Self {
+        self.signature = Some(signature.into());
+        self
+    }
+}
+
+/// Transaction ids already logged, with the time they were first seen
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PurchaseDedupe {
+    /// Storage directory the ids are persisted to, if any
+    storage_dir: Option<PathBuf>,
+
+    seen: HashMap<String, u64>,
+}
+
+impl PurchaseDedupe {
+    /// Load the ids persisted in the storage directory
+    ///
+    /// If the file can't be read, persistence is disabled rather than
+    /// overwriting it.
+    pub(crate) fn load(storage_dir: PathBuf) -> Self {
+        match storage::PURCHASE_IDS.load(&storage_dir) {
+            Ok(seen) => Self {
+                storage_dir: Some(storage_dir),
+                seen: seen.unwrap_or_default(),
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Whether a transaction id was recorded within `window` before `now`
+    pub(crate) fn is_duplicate(
+        &mut self,
+        transaction_id: &str,
+        now: u64,
+        window: Duration,
+    ) -> bool {
+        self.seen
+            .retain(|_, logged_at| now.saturating_sub(*logged_at) < window.as_secs());
+        self.seen.contains_key(transaction_id)
+    }
+
+    /// Record a transaction id as logged at `now`
+    pub(crate) fn record(&mut self, transaction_id: String, now: u64) {
+        self.seen.insert(transaction_id, now);
+    }
+
+    /// Forget every transaction id
+    pub(crate) fn clear(&mut self) {
+        self.seen.clear();
+    }
+
+    /// Persist the ids, if a storage directory is set
+    pub(crate) fn save(&self) -> io::Result<()> {
+        match &self.storage_dir {
+            Some(dir) => storage::PURCHASE_IDS.save(dir, &self.seen),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_purchase_ids_expire_and_persist() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", uuid::Uuid::new_v4()));
+        let window = Duration::from_secs(60);
+
+        let mut dedupe = PurchaseDedupe::load(dir.clone());
+        assert!(!dedupe.is_duplicate("order-1", 1_000, window));
+        dedupe.record("order-1".to_string(), 1_000);
+        assert!(dedupe.is_duplicate("order-1", 1_010, window));
+        dedupe.save().unwrap();
+
+        let mut dedupe = PurchaseDedupe::load(dir.clone());
+        assert!(dedupe.is_duplicate("order-1", 1_059, window));
+        assert!(!dedupe.is_duplicate("order-1", 1_060, window));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_verified_purchase_carries_receipt() {
+        let transport = std::sync::Arc::new(crate::MemoryTransport::new());
+        let mut client = crate::GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+
+        let receipt = PurchaseReceipt::new(Store::GooglePlay, r#"{"orderId":"GPA.1"}"#)
+            .signature("c2lnbmF0dXJl");
+        let event = crate::GameEventsIOEvent::purchase("gem_pack", 4.99, "USD")
+            .user_id("user123")
+            .session_id("session456")
+            .build()
+            .unwrap();
+        assert!(client
+            .log_verified_purchase("GPA.1", receipt, event.clone())
+            .unwrap());
+        let receipt = PurchaseReceipt::new(Store::GooglePlay, "");
+        assert!(!client
+            .log_verified_purchase("GPA.1", receipt, event)
+            .unwrap());
+        client.flush().unwrap();
+
+        let sent = serde_json::to_value(&transport.events()[0]).unwrap();
+        assert_eq!(
+            sent["receipt"],
+            serde_json::json!({
+                "store": "google_play",
+                "receipt": r#"{"orderId":"GPA.1"}"#,
+                "signature": "c2lnbmF0dXJl",
+            })
+        );
+        assert_eq!(sent["event_properties"][TRANSACTION_ID_PROPERTY], "GPA.1");
+    }
+}
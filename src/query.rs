@@ -0,0 +1,147 @@
+//! Queries over buffered events.
+//!
+//! Lets game logic ask the SDK about events it hasn't sent yet (e.g. "was
+//! the offer already shown today?") instead of keeping parallel state.
+
+use crate::GameEventsIOEvent;
+
+/// Filter over buffered events; every set condition must match
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EventQuery {
+    name: Option<String>,
+    since: Option<u64>,
+    until: Option<u64>,
+    user_id: Option<String>,
+    session_id: Option<String>,
+    properties: Vec<(String, serde_json::Value)>,
+    limit: Option<usize>,
+}
+
+impl EventQuery {
+    /// Match every event
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Match events with this name
+    pub fn name(name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Only events at or after this Unix timestamp in seconds
+    pub fn since(mut self, time: u64) -> Self {
+        self.since = Some(time);
+        self
+    }
+
+    /// Only events before this Unix timestamp in seconds
+    pub fn until(mut self, time: u64) -> Self {
+        self.until = Some(time);
+        self
+    }
+
+    /// Only events of this user
+    pub fn user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Only events of this session
+    pub fn session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Only events whose event property `key` equals `value`
+    pub fn property(mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
+        self.properties.push((key.into(), value.into()));
+        self
+    }
+
+    /// Return at most `limit` events (the oldest first)
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Check whether an event matches the query (ignoring the limit)
+    pub fn matches(&self, event: &GameEventsIOEvent) -> bool {
+        if matches!(&self.name, Some(name) if event.event != *name) {
+            return false;
+        }
+        if matches!(self.since, Some(since) if event.time < since) {
+            return false;
+        }
+        if matches!(self.until, Some(until) if event.time >= until) {
+            return false;
+        }
+        if matches!(&self.user_id, Some(user_id) if event.user_id != *user_id) {
+            return false;
+        }
+        if matches!(&self.session_id, Some(session_id) if event.session_id != *session_id) {
+            return false;
+        }
+        self.properties
+            .iter()
+            .all(|(key, value)| event.event_properties.get(key) == Some(value))
+    }
+
+    /// Matching events, in buffer order
+    pub(crate) fn run<'a>(
+        &self,
+        events: impl IntoIterator<Item = &'a GameEventsIOEvent>,
+    ) -> Vec<&'a GameEventsIOEvent> {
+        events
+            .into_iter()
+            .filter(|event| self.matches(event))
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameEventsIOEventBuilder;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_query_filters() {
+        let event = |name: &str, time: u64, offer: &str| {
+            GameEventsIOEventBuilder::default()
+                .event(name)
+                .user_id("user123")
+                .session_id("session456")
+                .time(time)
+                .event_properties(HashMap::from([(
+                    "offer_id".to_string(),
+                    serde_json::json!(offer),
+                )]))
+                .build()
+                .unwrap()
+        };
+        let events = vec![
+            event("offer_shown", 100, "starter_pack"),
+            event("offer_shown", 200, "gem_bundle"),
+            event("purchase", 300, "gem_bundle"),
+        ];
+
+        let found = EventQuery::name("offer_shown").since(150).run(&events);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].time, 200);
+
+        let found = EventQuery::all()
+            .property("offer_id", "gem_bundle")
+            .run(&events);
+        assert_eq!(found.len(), 2);
+
+        assert_eq!(EventQuery::all().until(300).limit(1).run(&events).len(), 1);
+        assert!(EventQuery::name("offer_shown")
+            .user_id("someone_else")
+            .run(&events)
+            .is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+//! Disk-backed queue of unsent events.
+//!
+//! With `GameEventsIOClientBuilder::persist_to`, buffered events are appended
+//! to a JSON lines file as they are logged, and the file is rewritten with
+//! the remaining events after each successful send. Events survive crashes
+//! and offline sessions and are reloaded when the next client is built.
+//!
+//! The first line is a `{"version": N}` header, each following line one event.
+//! Lines also carry the event's `priority`, which is not part of the wire
+//! format, so a restored event keeps the priority it was logged with.
+
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{EventPriority, GameEventsIOEvent};
+
+/// Queue file format written by this SDK
+pub(crate) const QUEUE_VERSION: u64 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct QueueHeader {
+    version: u64,
+}
+
+/// Queue line: an event and its local-only fields
+#[derive(Serialize)]
+struct QueuedEventRef<'a> {
+    #[serde(flatten)]
+    event: &'a GameEventsIOEvent,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    priority: Option<EventPriority>,
+}
+
+/// Queue line read back
+#[derive(Deserialize)]
+struct QueuedEvent {
+    #[serde(flatten)]
+    event: GameEventsIOEvent,
+
+    #[serde(default)]
+    priority: Option<EventPriority>,
+}
+
+impl From<QueuedEvent> for GameEventsIOEvent {
+    fn from(queued: QueuedEvent) -> Self {
+        GameEventsIOEvent {
+            priority: queued.priority,
+            ..queued.event
+        }
+    }
+}
+
+/// Append-only event file
+#[derive(Clone, Debug)]
+pub(crate) struct EventQueue {
+    path: PathBuf,
+}
+
+impl EventQueue {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Load the events left over from a previous run
+    ///
+    /// The file is compacted afterwards, dropping any line cut short by a
+    /// crash. A file that can't be read (e.g. from a newer SDK) is moved
+    /// aside to `<path>.unreadable` instead of being overwritten.
+    pub(crate) fn restore(&self) -> Vec<GameEventsIOEvent> {
+        let events = match self.load() {
+            Ok(events) => events,
+            Err(_) => {
+                let _ = fs::rename(&self.path, sibling(&self.path, ".unreadable"));
+                Vec::new()
+            }
+        };
+        let _ = self.rewrite(&events);
+        events
+    }
+
+    fn load(&self) -> io::Result<Vec<GameEventsIOEvent>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut lines = contents.lines();
+        let Some(header) = lines.next() else {
+            return Ok(Vec::new());
+        };
+        let header: QueueHeader = serde_json::from_str(header)?;
+        if header.version > QUEUE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Event queue has unsupported version {}", header.version),
+            ));
+        }
+
+        // Skip lines that don't parse rather than losing the whole queue
+        Ok(lines
+            .filter_map(|line| serde_json::from_str::<QueuedEvent>(line).ok())
+            .map(GameEventsIOEvent::from)
+            .collect())
+    }
+
+    /// Append newly buffered events
+    pub(crate) fn append(&self, events: &[GameEventsIOEvent]) -> io::Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        create_parent(&self.path)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut buf = Vec::new();
+        if file.metadata()?.len() == 0 {
+            write_header(&mut buf)?;
+        }
+        for event in events {
+            write_event(&mut buf, event)?;
+        }
+        file.write_all(&buf)
+    }
+
+    /// Replace the file contents with `events` (the ones still unsent)
+    pub(crate) fn rewrite(&self, events: &[GameEventsIOEvent]) -> io::Result<()> {
+        self.write_all(events, false)
+    }
+
+    /// Like `rewrite`, but only returns once the data has reached the disk
+    pub(crate) fn rewrite_synced(&self, events: &[GameEventsIOEvent]) -> io::Result<()> {
+        self.write_all(events, true)
+    }
+
+    fn write_all(&self, events: &[GameEventsIOEvent], sync: bool) -> io::Result<()> {
+        let mut buf = Vec::new();
+        write_header(&mut buf)?;
+        for event in events {
+            write_event(&mut buf, event)?;
+        }
+
+        create_parent(&self.path)?;
+        let tmp = sibling(&self.path, ".tmp");
+        let mut file = File::create(&tmp)?;
+        file.write_all(&buf)?;
+        if sync {
+            file.sync_all()?;
+        }
+        drop(file);
+        fs::rename(tmp, &self.path)
+    }
+}
+
+fn write_header(buf: &mut Vec<u8>) -> io::Result<()> {
+    write_line(
+        buf,
+        &QueueHeader {
+            version: QUEUE_VERSION,
+        },
+    )
+}
+
+fn write_event(buf: &mut Vec<u8>, event: &GameEventsIOEvent) -> io::Result<()> {
+    write_line(
+        buf,
+        &QueuedEventRef {
+            event,
+            priority: event.priority,
+        },
+    )
+}
+
+fn write_line(buf: &mut Vec<u8>, value: &impl Serialize) -> io::Result<()> {
+    serde_json::to_writer(&mut *buf, value)?;
+    buf.push(b'\n');
+    Ok(())
+}
+
+fn create_parent(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(dir) => fs::create_dir_all(dir),
+        None => Ok(()),
+    }
+}
+
+/// `path` with `suffix` appended to the file name
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameEventsIOEventBuilder;
+
+    fn test_event(name: &str) -> GameEventsIOEvent {
+        GameEventsIOEventBuilder::default()
+            .event(name)
+            .user_id("user123")
+            .session_id("session456")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_queue_survives_partial_writes() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", uuid::Uuid::new_v4()));
+        let queue = EventQueue::new(dir.join("queue.jsonl"));
+
+        queue
+            .append(&[test_event("first"), test_event("second")])
+            .unwrap();
+        // Simulate a crash in the middle of an append
+        let mut file = OpenOptions::new().append(true).open(&queue.path).unwrap();
+        file.write_all(br#"{"event":"thi"#).unwrap();
+
+        let events = queue.restore();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].event, "second");
+
+        queue.append(&[test_event("third")]).unwrap();
+        assert_eq!(queue.restore().len(), 3);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_queue_from_newer_sdk_is_kept() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let queue = EventQueue::new(dir.join("queue.jsonl"));
+        fs::write(&queue.path, "{\"version\":99}\n").unwrap();
+
+        assert!(queue.restore().is_empty());
+        assert!(dir.join("queue.jsonl.unreadable").is_file());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_queue_keeps_priority() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", uuid::Uuid::new_v4()));
+        let queue = EventQueue::new(dir.join("queue.jsonl"));
+
+        let mut low = test_event("frame_time");
+        low.priority = Some(EventPriority::Low);
+        queue
+            .append(&[low.clone(), test_event("purchase")])
+            .unwrap();
+
+        let events = queue.restore();
+        assert_eq!(events[0].priority, Some(EventPriority::Low));
+        assert_eq!(events[1].priority, None);
+        assert!(events[0].extra.is_empty());
+        // Still local-only on the wire
+        assert!(serde_json::to_value(&low)
+            .unwrap()
+            .get("priority")
+            .is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
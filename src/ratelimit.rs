@@ -0,0 +1,76 @@
+//! Token-bucket limit on how many events the client buffers.
+//!
+//! The bucket holds one minute's worth of events and refills continuously,
+//! so short bursts pass while the long-run rate stays under the quota.
+//! Critical events (purchases, crashes) are never rate limited.
+
+use std::time::Instant;
+
+/// Limit on the number of events logged per minute
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    events_per_minute: u32,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Allow `events_per_minute` events per minute, starting with a full bucket
+    pub fn per_minute(events_per_minute: u32) -> Self {
+        Self {
+            events_per_minute,
+            tokens: f64::from(events_per_minute),
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Configured rate
+    pub fn events_per_minute(&self) -> u32 {
+        self.events_per_minute
+    }
+
+    /// Take a token for one event, returning whether the event may pass
+    pub fn try_acquire(&mut self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&mut self, now: Instant) -> bool {
+        let capacity = f64::from(self.events_per_minute);
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * capacity / 60.0).min(capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_token_bucket_refills() {
+        let mut limiter = RateLimiter::per_minute(60);
+        let start = limiter.last_refill;
+
+        assert_eq!(
+            (0..100).filter(|_| limiter.try_acquire_at(start)).count(),
+            60
+        );
+        assert!(!limiter.try_acquire_at(start + Duration::from_millis(500)));
+        assert!(limiter.try_acquire_at(start + Duration::from_secs(1)));
+
+        // Refills up to one minute's worth, no more
+        let later = start + Duration::from_secs(3600);
+        assert_eq!(
+            (0..100).filter(|_| limiter.try_acquire_at(later)).count(),
+            60
+        );
+    }
+}
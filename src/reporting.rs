@@ -0,0 +1,222 @@
+//! Read-only client for the game-events.io reporting API (feature `reporting`).
+//!
+//! Lets LiveOps tools pull dashboard data programmatically: daily active
+//! users, retention curves and funnel results. Authenticates with the same
+//! API key as the SDK. Dates are ISO 8601 calendar dates (`"2026-10-01"`).
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::transport::{self, TransportResponse};
+use crate::{FlushResult, GameEventsIOClient, GameEventsIOError, NetworkConfig};
+
+/// Default game-events.io endpoint of the reporting API
+pub const DEFAULT_REPORTING_URL: &str = "https://api.game-events.io/v1/reports";
+
+/// Daily active users on one day
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[non_exhaustive]
+pub struct DailyActiveUsers {
+    /// Calendar date
+    pub date: String,
+
+    /// Distinct users with at least one event that day
+    pub users: u64,
+}
+
+/// Share of a cohort still active N days after its first day
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[non_exhaustive]
+pub struct RetentionCurve {
+    /// Date the cohort's users were first seen
+    pub cohort: String,
+
+    /// Number of users in the cohort
+    pub cohort_size: u64,
+
+    /// `retention[n]` is the share (0.0 to 1.0) of the cohort active on day n
+    pub retention: Vec<f64>,
+}
+
+/// Users who reached one step of a funnel
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[non_exhaustive]
+pub struct FunnelStepResult {
+    /// Step name
+    pub step: String,
+
+    /// Distinct users who completed the step
+    pub users: u64,
+}
+
+/// Results of a funnel over a date range
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[non_exhaustive]
+pub struct FunnelReport {
+    /// Funnel name
+    pub funnel: String,
+
+    /// Steps in funnel order
+    pub steps: Vec<FunnelStepResult>,
+}
+
+impl FunnelReport {
+    /// Share of the users of the first step who completed the last one
+    pub fn conversion(&self) -> Option<f64> {
+        let first = self.steps.first()?.users;
+        let last = self.steps.last()?.users;
+        if first == 0 {
+            return None;
+        }
+        Some(last as f64 / first as f64)
+    }
+}
+
+#[derive(Deserialize)]
+struct DauResponse {
+    days: Vec<DailyActiveUsers>,
+}
+
+/// Client for the reporting API
+#[derive(Clone, Debug)]
+pub struct ReportingClient {
+    url: String,
+    api_key: String,
+    /// `None` if the HTTP client could not be built
+    client: Option<reqwest::blocking::Client>,
+}
+
+impl ReportingClient {
+    /// Create a client for the default reporting endpoint
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            url: DEFAULT_REPORTING_URL.to_string(),
+            api_key: api_key.into(),
+            client: NetworkConfig::default().blocking_client(),
+        }
+    }
+
+    /// Create a client with the API key and network settings of an SDK client
+    pub fn for_client(client: &GameEventsIOClient) -> Self {
+        Self::new(client.api_key()).network_config(client.network_config().clone())
+    }
+
+    /// Use custom TLS, proxy and timeout settings
+    pub fn network_config(mut self, config: NetworkConfig) -> Self {
+        self.client = config.blocking_client();
+        self
+    }
+
+    /// Query another reporting endpoint (e.g. a staging backend)
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Daily active users for each day from `from` to `to`, inclusive
+    pub fn daily_active_users(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<DailyActiveUsers>, GameEventsIOError> {
+        let response: DauResponse = self.get("dau", &[("from", from), ("to", to)])?;
+        Ok(response.days)
+    }
+
+    /// Retention of the users first seen on `cohort`, for `days` days
+    pub fn retention(&self, cohort: &str, days: u32) -> Result<RetentionCurve, GameEventsIOError> {
+        self.get(
+            "retention",
+            &[("cohort", cohort), ("days", &days.to_string())],
+        )
+    }
+
+    /// Results of the funnel named `funnel` from `from` to `to`, inclusive
+    pub fn funnel(
+        &self,
+        funnel: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<FunnelReport, GameEventsIOError> {
+        self.get("funnel", &[("funnel", funnel), ("from", from), ("to", to)])
+    }
+
+    fn get<T: DeserializeOwned>(
+        &self,
+        report: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, GameEventsIOError> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| GameEventsIOError::Transport("HTTP client unavailable".to_string()))?;
+        let mut request = client
+            .get(format!("{}/{}", self.url.trim_end_matches('/'), report))
+            .query(query);
+        if !self.api_key.is_empty() {
+            request = request.header("Authorization", format!("Bearer {}", self.api_key));
+        }
+        let response = request.send()?;
+        let response = TransportResponse {
+            status: response.status().as_u16(),
+            retry_after: transport::parse_retry_after(response.headers()),
+            server_time: transport::parse_server_time(response.headers()),
+            body: response.text()?,
+        };
+        let body = FlushResult::from_response(response, 0)?.body;
+        Ok(serde_json::from_str(&body).map_err(std::io::Error::from)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Answer one request with `body`, returning the request line
+    fn serve_once(listener: TcpListener, body: &'static str) -> std::thread::JoinHandle<String> {
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        })
+    }
+
+    #[test]
+    fn test_reports_are_typed() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/v1/reports", listener.local_addr().unwrap());
+        let backend = serve_once(
+            listener,
+            r#"{"funnel":"onboarding","steps":[{"step":"tutorial","users":200},{"step":"first_match","users":50}]}"#,
+        );
+
+        let client = ReportingClient::new("test_api_key").url(url);
+        let report = client
+            .funnel("onboarding", "2026-10-01", "2026-10-07")
+            .unwrap();
+        assert_eq!(report.steps[1].step, "first_match");
+        assert_eq!(report.conversion(), Some(0.25));
+
+        let request = backend.join().unwrap();
+        assert!(request.starts_with(
+            "GET /v1/reports/funnel?funnel=onboarding&from=2026-10-01&to=2026-10-07 "
+        ));
+        assert!(request.contains("Bearer test_api_key"));
+    }
+}
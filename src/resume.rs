@@ -0,0 +1,230 @@
+//! Session merging across quick relaunches.
+//!
+//! A crash loop or a quick restart would otherwise split one play period
+//! into many tiny sessions. `SessionManager` persists the current session
+//! and, on the next launch, resumes it if the game was gone for less than
+//! the grace window.
+
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    diagnostics, identity, storage, unix_now, GameEventsIOError, GameEventsIOSession,
+    GameEventsIOSessionBuilder,
+};
+
+/// Resumes the previous session after a quick relaunch
+#[derive(Clone, Debug)]
+pub struct SessionManager {
+    /// Storage directory the last session is persisted to, if any
+    storage_dir: Option<PathBuf>,
+
+    /// Longest absence after which the previous session is resumed
+    grace_window: Duration,
+
+    state: Option<LastSession>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct LastSession {
+    user_id: String,
+    session_id: String,
+
+    /// Unix timestamp the session started at
+    started_at: u64,
+
+    /// Unix timestamp of the last checkpoint
+    last_activity: u64,
+}
+
+impl Default for SessionManager {
+    fn default() -> Self {
+        Self {
+            storage_dir: None,
+            grace_window: Duration::from_secs(5 * 60),
+            state: None,
+        }
+    }
+}
+
+impl SessionManager {
+    /// Create a manager that forgets the session on restart
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load the last session persisted in the SDK storage directory
+    pub fn load(storage_dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let storage_dir = storage_dir.into();
+        let state = storage::LAST_SESSION.load(&storage_dir)?;
+
+        Ok(Self {
+            storage_dir: Some(storage_dir),
+            state,
+            ..Self::default()
+        })
+    }
+
+    /// Resume sessions last active at most `grace_window` ago (default: 5 minutes)
+    pub fn with_grace_window(mut self, grace_window: Duration) -> Self {
+        self.grace_window = grace_window;
+        self
+    }
+
+    /// Session to use after a launch at Unix timestamp `now`
+    ///
+    /// Resumes the persisted session, without a new_session event, if it was
+    /// active within the grace window; otherwise starts a new session for
+    /// the same user. The result is checkpointed right away.
+    pub fn resume_or_rotate(&mut self, now: u64) -> GameEventsIOSession {
+        self.resume_or_rotate_with(&GameEventsIOSessionBuilder::default(), now)
+    }
+
+    /// Same as `resume_or_rotate`, building the session from `template`
+    ///
+    /// Use it to set the inactivity timeout, summary and providers. The
+    /// user id and session id of the template are replaced by the ones of
+    /// the resumed or new session.
+    pub fn resume_or_rotate_with(
+        &mut self,
+        template: &GameEventsIOSessionBuilder,
+        now: u64,
+    ) -> GameEventsIOSession {
+        let grace = self.grace_window.as_secs();
+        let session = match self.state.clone() {
+            Some(last) if now.saturating_sub(last.last_activity) <= grace => {
+                let mut session = diagnostics::recover(
+                    template
+                        .clone()
+                        .user_id(last.user_id.clone())
+                        .session_id(last.session_id.clone())
+                        .build_session(),
+                    "Failed to resume GameEventsIOSession",
+                    || GameEventsIOSession::fallback(last.user_id, last.session_id),
+                );
+                *session.started_at.get_mut() = last.started_at;
+                *session.last_activity.get_mut() = now;
+                session
+            }
+            last => {
+                let user_id = match last {
+                    Some(last) => last.user_id,
+                    None => self.first_user_id(),
+                };
+                let session_id = Uuid::new_v4().to_string();
+                let mut session = diagnostics::recover(
+                    template
+                        .clone()
+                        .user_id(user_id.clone())
+                        .session_id(session_id.clone())
+                        .build(),
+                    "Failed to create GameEventsIOSession",
+                    || GameEventsIOSession::started_fallback(user_id, session_id),
+                );
+                *session.started_at.get_mut() = now;
+                *session.last_activity.get_mut() = now;
+                session
+            }
+        };
+
+        if let Err(e) = self.checkpoint_at(&session, now) {
+            diagnostics::report(&GameEventsIOError::Io(e));
+        }
+        session
+    }
+
+    /// User id of the first session: the persisted anonymous id, if stored
+    fn first_user_id(&self) -> String {
+        let Some(dir) = &self.storage_dir else {
+            return Uuid::new_v4().to_string();
+        };
+        identity::anonymous_id(dir).unwrap_or_else(|e| {
+            diagnostics::report(&GameEventsIOError::Io(e));
+            Uuid::new_v4().to_string()
+        })
+    }
+
+    /// Persist the session so a relaunch can resume it
+    ///
+    /// Call it periodically (e.g. on every flush) and when the game goes to
+    /// background; the grace window counts from the last checkpoint.
+    pub fn checkpoint(&mut self, session: &GameEventsIOSession) -> io::Result<()> {
+        self.checkpoint_at(session, unix_now())
+    }
+
+    /// Same as `checkpoint`, with an explicit Unix timestamp in seconds
+    pub fn checkpoint_at(&mut self, session: &GameEventsIOSession, now: u64) -> io::Result<()> {
+        let state = LastSession {
+            user_id: session.user_id(),
+            session_id: session.session_id(),
+            started_at: session.started_at.load(Ordering::Relaxed),
+            last_activity: now,
+        };
+        let result = match &self.storage_dir {
+            Some(dir) => storage::LAST_SESSION.save(dir, &state),
+            None => Ok(()),
+        };
+        self.state = Some(state);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EVENT_NEW_SESSION;
+    use std::fs;
+
+    const LAUNCH: u64 = 1_700_006_400;
+
+    #[test]
+    fn test_quick_relaunch_resumes_session() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", Uuid::new_v4()));
+
+        let mut manager = SessionManager::load(&dir).unwrap();
+        let first = manager.resume_or_rotate(LAUNCH);
+        assert_eq!(first.take_events(10)[0].event, EVENT_NEW_SESSION);
+        manager.checkpoint_at(&first, LAUNCH + 600).unwrap();
+
+        // Crash and relaunch two minutes after the last checkpoint
+        let mut manager = SessionManager::load(&dir).unwrap();
+        let resumed = manager.resume_or_rotate(LAUNCH + 720);
+        assert_eq!(resumed.session_id(), first.session_id());
+        assert_eq!(resumed.started_at.load(Ordering::Relaxed), LAUNCH);
+        assert!(resumed.take_events(10).is_empty());
+
+        // Relaunch after the grace window
+        let mut manager = SessionManager::load(&dir)
+            .unwrap()
+            .with_grace_window(Duration::from_secs(60));
+        let rotated = manager.resume_or_rotate(LAUNCH + 900);
+        assert_ne!(rotated.session_id(), first.session_id());
+        assert_eq!(rotated.user_id(), first.user_id());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resume_with_template() {
+        let template = GameEventsIOSessionBuilder::default()
+            .inactivity_timeout(Duration::from_secs(60))
+            .emit_summary()
+            .clone();
+
+        let mut manager = SessionManager::in_memory();
+        let first = manager.resume_or_rotate_with(&template, LAUNCH);
+        assert_eq!(first.inactivity_timeout, Some(Duration::from_secs(60)));
+        assert!(first.summary().is_some());
+
+        let resumed = manager.resume_or_rotate_with(&template, LAUNCH + 30);
+        assert_eq!(resumed.session_id(), first.session_id());
+        assert_eq!(resumed.user_id(), first.user_id());
+        assert!(resumed.summary().is_some());
+        assert!(resumed.take_events(10).is_empty());
+    }
+}
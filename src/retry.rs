@@ -0,0 +1,180 @@
+//! Retrying failed uploads with exponential backoff.
+
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use crate::{GameEventsIOError, GameEventsIOEvent};
+
+/// How failed batches are retried before they go back into the buffer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Total number of send attempts per flush (1 disables retries)
+    pub max_attempts: u32,
+
+    /// Delay before the first retry, doubled for each further retry
+    pub initial_backoff: Duration,
+
+    /// Upper bound on the delay between two attempts
+    pub max_backoff: Duration,
+
+    /// Random spread applied to each delay, from 0.0 (none) to 1.0 (up to ±100%)
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Send once and keep failed batches buffered for the next flush
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retry number `retry` (starting at 1), without jitter
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Delay before retry number `retry`, with jitter applied
+    pub(crate) fn delay(&self, retry: u32) -> Duration {
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        // Uniform in [-1, 1), taken from the v4 UUID's random bits
+        let random = (Uuid::new_v4().as_u128() as u32) as f64 / u32::MAX as f64 * 2.0 - 1.0;
+        self.backoff(retry).mul_f64(1.0 + jitter * random)
+    }
+
+    /// How long to wait before retrying after attempt number `attempt` failed
+    ///
+    /// `None` if the error is final, the attempts are used up, or the
+    /// backend's `Retry-After` is longer than `max_backoff`.
+    pub(crate) fn next_delay(&self, attempt: u32, err: &GameEventsIOError) -> Option<Duration> {
+        if !err.is_retryable() || attempt >= self.max_attempts {
+            return None;
+        }
+        let retry_after = err.retry_after().unwrap_or_default();
+        if retry_after > self.max_backoff {
+            // Don't block the game for long waits, try again on the next flush
+            return None;
+        }
+        Some(self.delay(attempt).min(self.max_backoff).max(retry_after))
+    }
+
+    /// Whether a response with this status is worth retrying
+    pub fn is_retryable_status(status: u16) -> bool {
+        status == 408 || status == 429 || (500..600).contains(&status)
+    }
+}
+
+/// Where the events of a batch go once its delivery failed for good
+///
+/// Batches failing with 413 are split by the caller before this.
+#[derive(Debug, Default)]
+pub(crate) struct FailedBatch {
+    /// Events to put back at the front of the buffer
+    pub(crate) requeue: Vec<GameEventsIOEvent>,
+
+    /// Events the backend refused for good
+    pub(crate) dead_letters: Vec<GameEventsIOEvent>,
+
+    /// Whether uploads must pause until the API key is fixed
+    pub(crate) pause: bool,
+}
+
+impl FailedBatch {
+    /// Sort the events of `batch` after it failed with `err`
+    ///
+    /// - retryable errors (5xx, timeouts, 429) requeue the batch
+    /// - 401/403 requeue the batch and pause uploads
+    /// - invalid events are dead-lettered, the others in the batch requeued
+    /// - other errors dead-letter the batch
+    pub(crate) fn sort(err: &GameEventsIOError, batch: Vec<GameEventsIOEvent>) -> Self {
+        match err {
+            GameEventsIOError::InvalidEvents { rejected_indices } => {
+                let (rejected, valid): (Vec<_>, Vec<_>) = batch
+                    .into_iter()
+                    .enumerate()
+                    .partition(|(index, _)| rejected_indices.contains(index));
+                Self {
+                    requeue: valid.into_iter().map(|(_, event)| event).collect(),
+                    dead_letters: rejected.into_iter().map(|(_, event)| event).collect(),
+                    pause: false,
+                }
+            }
+            GameEventsIOError::Unauthorized => Self {
+                requeue: batch,
+                pause: true,
+                ..Self::default()
+            },
+            err if err.is_retryable() => Self {
+                requeue: batch,
+                ..Self::default()
+            },
+            _ => Self {
+                dead_letters: batch,
+                ..Self::default()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            jitter: 0.5,
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+
+        for _ in 0..100 {
+            let delay = policy.delay(2);
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(300));
+        }
+
+        // Jitter never pushes a delay past `max_backoff`, only `Retry-After` does
+        let server_error = GameEventsIOError::Server {
+            status: 503,
+            body: String::new(),
+        };
+        for _ in 0..100 {
+            let delay = policy.next_delay(5, &server_error).unwrap();
+            assert!(delay <= Duration::from_millis(500));
+        }
+        let rate_limited = |millis| GameEventsIOError::RateLimited {
+            retry_after: Some(Duration::from_millis(millis)),
+        };
+        assert_eq!(
+            policy.next_delay(1, &rate_limited(400)),
+            Some(Duration::from_millis(400))
+        );
+        assert_eq!(policy.next_delay(1, &rate_limited(1000)), None);
+        assert_eq!(policy.next_delay(10, &server_error), None);
+
+        assert!(RetryPolicy::is_retryable_status(503));
+        assert!(RetryPolicy::is_retryable_status(429));
+        assert!(!RetryPolicy::is_retryable_status(400));
+    }
+}
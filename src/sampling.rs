@@ -0,0 +1,214 @@
+//! Client-side sampling of events.
+//!
+//! Sampling individual events breaks funnels: a player may show up at step 3
+//! without step 2. `SamplingMode::PerSession` instead keeps or drops whole
+//! sessions, decided deterministically from the session id, so every event of
+//! a kept session arrives. Rules are chosen per event name, then per event
+//! category, identified by an event name prefix (e.g. `"combat_"`).
+
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::GameEventsIOEvent;
+
+/// Event property holding the rate an event was sampled at (set when below 1)
+pub const SAMPLE_RATE_PROPERTY: &str = "sample_rate";
+
+/// How the keep/drop decision is made
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SamplingMode {
+    /// Decide independently for every event
+    PerEvent,
+
+    /// Keep or drop every event of a session together
+    #[default]
+    PerSession,
+}
+
+/// Fraction of events to keep and how to pick them
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SamplingRule {
+    /// Fraction kept, from 0.0 (drop everything) to 1.0 (keep everything)
+    pub rate: f64,
+
+    /// How events are picked
+    pub mode: SamplingMode,
+}
+
+impl SamplingRule {
+    /// Keep every event
+    pub fn keep_all() -> Self {
+        Self::per_session(1.0)
+    }
+
+    /// Keep a fraction of sessions, with all of their events
+    pub fn per_session(rate: f64) -> Self {
+        Self {
+            rate,
+            mode: SamplingMode::PerSession,
+        }
+    }
+
+    /// Keep a fraction of events, picked independently
+    pub fn per_event(rate: f64) -> Self {
+        Self {
+            rate,
+            mode: SamplingMode::PerEvent,
+        }
+    }
+}
+
+impl Default for SamplingRule {
+    fn default() -> Self {
+        Self::keep_all()
+    }
+}
+
+/// Sampling rules for all events, with overrides per category and event name
+#[derive(Clone, Debug, Default)]
+pub struct Sampler {
+    default_rule: SamplingRule,
+
+    /// Event name prefix and its rule
+    categories: Vec<(String, SamplingRule)>,
+
+    /// Rules for single event names, taking precedence over categories
+    events: HashMap<String, SamplingRule>,
+}
+
+impl Sampler {
+    /// Create a sampler applying `default_rule` to events without a category rule
+    pub fn new(default_rule: SamplingRule) -> Self {
+        Self {
+            default_rule,
+            categories: Vec::new(),
+            events: HashMap::new(),
+        }
+    }
+
+    /// Use `rule` for events whose name starts with `prefix`
+    ///
+    /// The longest matching prefix wins.
+    pub fn category(mut self, prefix: impl Into<String>, rule: SamplingRule) -> Self {
+        self.categories.push((prefix.into(), rule));
+        self
+    }
+
+    /// Use `rule` for events named exactly `event_name`
+    pub fn event(mut self, event_name: impl Into<String>, rule: SamplingRule) -> Self {
+        self.events.insert(event_name.into(), rule);
+        self
+    }
+
+    /// Rule applied to events with this name
+    pub fn rule_for(&self, event_name: &str) -> SamplingRule {
+        if let Some(rule) = self.events.get(event_name) {
+            return *rule;
+        }
+        self.categories
+            .iter()
+            .filter(|(prefix, _)| event_name.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default_rule, |(_, rule)| *rule)
+    }
+
+    /// Check whether an event is kept
+    pub fn keeps(&self, event: &GameEventsIOEvent) -> bool {
+        let rule = self.rule_for(&event.event);
+        if rule.rate >= 1.0 {
+            return true;
+        }
+        let roll = match rule.mode {
+            SamplingMode::PerSession => stable_hash(event.session_id.as_bytes()),
+            SamplingMode::PerEvent => Uuid::new_v4().as_u128() as u64,
+        };
+        (roll as f64 / u64::MAX as f64) < rule.rate
+    }
+
+    /// Decide on an event, tagging it with its sample rate if kept
+    pub(crate) fn sample(&self, event: &mut GameEventsIOEvent) -> bool {
+        if !self.keeps(event) {
+            return false;
+        }
+        let rate = self.rule_for(&event.event).rate;
+        if rate < 1.0 {
+            event
+                .event_properties
+                .insert(SAMPLE_RATE_PROPERTY.to_string(), serde_json::json!(rate));
+        }
+        true
+    }
+}
+
+/// 64-bit hash stable across platforms and releases
+///
+/// FNV-1a, followed by the murmur3 finalizer so ids differing only in their
+/// last characters still spread over the whole range.
+fn stable_hash(bytes: &[u8]) -> u64 {
+    let mut hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameEventsIOEventBuilder;
+
+    fn event(name: &str, session_id: &str) -> GameEventsIOEvent {
+        GameEventsIOEventBuilder::default()
+            .event(name)
+            .user_id("user123")
+            .session_id(session_id)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_per_session_sampling_keeps_whole_sessions() {
+        let sampler = Sampler::new(SamplingRule::per_session(0.5));
+
+        let mut kept_sessions = 0;
+        for i in 0..1000 {
+            let session_id = format!("session-{}", i);
+            let kept = sampler.keeps(&event("level_started", &session_id));
+            assert_eq!(sampler.keeps(&event("level_completed", &session_id)), kept);
+            kept_sessions += kept as usize;
+        }
+        assert!((400..600).contains(&kept_sessions));
+    }
+
+    #[test]
+    fn test_category_rules() {
+        let sampler = Sampler::new(SamplingRule::keep_all())
+            .category("combat_", SamplingRule::per_event(0.0))
+            .category("combat_boss_", SamplingRule::keep_all());
+
+        let mut hit = event("combat_hit", "session456");
+        assert!(!sampler.sample(&mut hit));
+        assert!(sampler.keeps(&event("combat_boss_defeated", "session456")));
+        assert!(sampler.keeps(&event("level_started", "session456")));
+
+        // Event name rules beat category rules
+        let sampler = sampler.event("combat_hit", SamplingRule::keep_all());
+        assert!(sampler.keeps(&event("combat_hit", "session456")));
+
+        // Kept events carry their sample rate
+        let sampler = Sampler::new(SamplingRule::per_session(0.5));
+        let mut kept = (0..100)
+            .map(|i| event("level_started", &format!("session-{}", i)))
+            .find(|event| sampler.keeps(event))
+            .unwrap();
+        assert!(sampler.sample(&mut kept));
+        assert_eq!(
+            kept.event_properties.get(SAMPLE_RATE_PROPERTY).unwrap(),
+            0.5
+        );
+    }
+}
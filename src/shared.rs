@@ -0,0 +1,183 @@
+//! Client handle shared between threads or systems.
+//!
+//! `GameEventsIOClient` owns its buffer and can't be cloned, so an event can
+//! never end up in two buffers. To log from several places, convert the
+//! client with `into_shared`: every clone of the handle uses the same client,
+//! and each event is sent exactly once by whichever handle flushes.
+
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{EventQuery, FlushResult, GameEventsIOClient, GameEventsIOError, GameEventsIOEvent};
+
+/// Cloneable handle to one client; clones share its buffer
+#[derive(Clone, Debug)]
+pub struct SharedClient {
+    client: Arc<Mutex<GameEventsIOClient>>,
+}
+
+impl SharedClient {
+    /// Wrap a client
+    pub fn new(client: GameEventsIOClient) -> Self {
+        Self {
+            client: Arc::new(Mutex::new(client)),
+        }
+    }
+
+    /// Create another handle to the same client (same as `clone`)
+    pub fn split_handle(&self) -> Self {
+        self.clone()
+    }
+
+    /// Log an event (adds to the shared buffer)
+    pub fn log_event(&self, event: GameEventsIOEvent) {
+        self.lock().log_event(event);
+    }
+
+    /// Send all buffered events to the backend
+    ///
+    /// Other handles wait while the flush is in progress.
+    pub fn flush(&self) -> Result<FlushResult, GameEventsIOError> {
+        self.lock().flush()
+    }
+
+    /// Send up to `batch_size` buffered events
+    pub fn flush_batch(&self, batch_size: usize) -> Result<FlushResult, GameEventsIOError> {
+        self.lock().flush_batch(batch_size)
+    }
+
+    /// Send the whole buffer in batches of `batch_size` (see `GameEventsIOClient::flush_all`)
+    pub fn flush_all(&self, batch_size: usize) -> Vec<Result<FlushResult, GameEventsIOError>> {
+        self.lock().flush_all(batch_size)
+    }
+
+    /// Get the number of buffered events
+    pub fn pending_events_count(&self) -> usize {
+        self.lock().pending_events_count()
+    }
+
+    /// Find buffered events, in the order they were logged
+    pub fn query(&self, query: EventQuery) -> Vec<GameEventsIOEvent> {
+        self.lock().query(query).into_iter().cloned().collect()
+    }
+
+    /// Write the shared buffer to the offline queue (see `GameEventsIOClient::persist_now`)
+    pub fn persist_now(&self) -> std::io::Result<()> {
+        self.lock().persist_now()
+    }
+
+    /// Same as `persist_now`, giving up if the client stays locked for `timeout`
+    ///
+    /// Fails with `ErrorKind::TimedOut` if another handle held the client the
+    /// whole time (e.g. a flush waiting on the network).
+    pub fn persist_within(&self, timeout: Duration) -> io::Result<()> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.client.try_lock() {
+                Ok(client) => return client.persist_now(),
+                Err(TryLockError::Poisoned(poisoned)) => {
+                    return poisoned.into_inner().persist_now()
+                }
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    thread::sleep(Duration::from_millis(5));
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "Client stayed locked, buffer not persisted",
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Access the client (e.g. to opt out or change settings)
+    pub fn lock(&self) -> MutexGuard<'_, GameEventsIOClient> {
+        self.client
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Number of handles to this client
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.client)
+    }
+}
+
+impl From<GameEventsIOClient> for SharedClient {
+    fn from(client: GameEventsIOClient) -> Self {
+        Self::new(client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameEventsIOClientBuilder, GameEventsIOEventBuilder, MemoryTransport};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_handles_share_one_buffer() {
+        let transport = Arc::new(MemoryTransport::new());
+        let shared = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .build()
+            .unwrap()
+            .into_shared();
+
+        let threads: Vec<_> = (0..4)
+            .map(|i| {
+                let handle = shared.split_handle();
+                thread::spawn(move || {
+                    for j in 0..25 {
+                        let event = GameEventsIOEventBuilder::default()
+                            .event(format!("event_{}_{}", i, j))
+                            .user_id("user123")
+                            .session_id("session456")
+                            .build()
+                            .unwrap();
+                        handle.log_event(event);
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(shared.pending_events_count(), 100);
+        assert_eq!(shared.flush().unwrap().accepted, 100);
+        assert_eq!(shared.clone().flush().unwrap().accepted, 0);
+        assert_eq!(transport.events().len(), 100);
+    }
+
+    #[test]
+    fn test_persist_within_gives_up_on_locked_client() {
+        let path = std::env::temp_dir().join(format!("game-events-sdk-{}.jsonl", Uuid::new_v4()));
+        let shared = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .persist_to(path.clone())
+            .build()
+            .unwrap()
+            .into_shared();
+        shared.log_event(
+            GameEventsIOEventBuilder::default()
+                .event("a")
+                .build()
+                .unwrap(),
+        );
+
+        let held = shared.lock();
+        let error = shared
+            .persist_within(Duration::from_millis(20))
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        drop(held);
+
+        shared.persist_within(Duration::from_millis(20)).unwrap();
+        let _ = std::fs::remove_file(&path);
+    }
+}
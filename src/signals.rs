@@ -0,0 +1,118 @@
+//! Save the buffer when the OS terminates the game (feature `signals`, Unix only).
+//!
+//! `persist_on_termination` listens for SIGTERM, SIGINT and SIGQUIT on a
+//! background thread. When one arrives, the client's buffer is written to
+//! the offline queue (see `GameEventsIOClient::persist_now`) and the signal's
+//! default action runs, so the process still exits as the OS asked. The next
+//! client built with the same `persist_to` path sends the saved events.
+//!
+//! If another thread holds the client (e.g. a flush stuck on the network),
+//! the handler waits at most `PERSIST_TIMEOUT` before letting the process
+//! exit without persisting.
+//!
+//! Signal handlers can't be removed once installed, so after the last guard
+//! is dropped they run the default action themselves: the signals terminate
+//! the process again, as if no handler had been installed.
+
+use std::io;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use signal_hook::consts::TERM_SIGNALS;
+use signal_hook::flag;
+use signal_hook::iterator::{Handle, Signals};
+use signal_hook::low_level;
+
+use crate::{diagnostics, SharedClient};
+
+/// How long the handler waits for a client locked by another thread
+pub const PERSIST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Number of installed guards
+static GUARDS: AtomicUsize = AtomicUsize::new(0);
+
+/// Whether termination signals run their default action, registered once
+static DEFAULT_ACTION: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+/// Flag turning on the default action of termination signals
+fn default_action() -> io::Result<&'static Arc<AtomicBool>> {
+    if let Some(enabled) = DEFAULT_ACTION.get() {
+        return Ok(enabled);
+    }
+    let enabled = Arc::new(AtomicBool::new(false));
+    for &signal in TERM_SIGNALS {
+        flag::register_conditional_default(signal, enabled.clone())?;
+    }
+    Ok(DEFAULT_ACTION.get_or_init(|| enabled))
+}
+
+/// Installed termination handler; uninstalled when dropped
+#[derive(Debug)]
+pub struct TerminationGuard {
+    handle: Handle,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TerminationGuard {
+    /// Stop persisting on termination; the signals terminate the process again
+    pub fn uninstall(self) {}
+}
+
+impl Drop for TerminationGuard {
+    fn drop(&mut self) {
+        if GUARDS.fetch_sub(1, Ordering::SeqCst) == 1 {
+            if let Some(enabled) = DEFAULT_ACTION.get() {
+                enabled.store(true, Ordering::SeqCst);
+            }
+        }
+        self.handle.close();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Persist `client`'s buffer before the process is terminated by a signal
+pub fn persist_on_termination(client: SharedClient) -> io::Result<TerminationGuard> {
+    let default_action = default_action()?;
+    let mut signals = Signals::new(TERM_SIGNALS)?;
+    let handle = signals.handle();
+    let thread = thread::Builder::new()
+        .name("game-events-signals".to_string())
+        .spawn(move || {
+            if let Some(signal) = signals.forever().next() {
+                if let Err(e) = client.persist_within(PERSIST_TIMEOUT) {
+                    diagnostics::report(&e.into());
+                }
+                if let Err(e) = low_level::emulate_default_handler(signal) {
+                    diagnostics::report(&e.into());
+                }
+            }
+        })?;
+
+    GUARDS.fetch_add(1, Ordering::SeqCst);
+    default_action.store(false, Ordering::SeqCst);
+    Ok(TerminationGuard {
+        handle,
+        thread: Some(thread),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameEventsIOClient;
+
+    #[test]
+    fn test_dropped_guard_restores_default_action() {
+        let guard =
+            persist_on_termination(GameEventsIOClient::new("test_api_key").into_shared()).unwrap();
+        let enabled = DEFAULT_ACTION.get().unwrap();
+        assert!(!enabled.load(Ordering::SeqCst));
+
+        guard.uninstall();
+        assert!(enabled.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,219 @@
+//! Typed constructors for standard game events.
+//!
+//! Each constructor returns an event builder with the canonical event name
+//! and property keys the backend dashboards expect; set the user and
+//! session and build as usual:
+//!
+//! ```no_run
+//! use game_events_sdk::GameEventsIOEvent;
+//!
+//! let event = GameEventsIOEvent::purchase("gem_pack", 4.99, "USD")
+//!     .user_id("user123")
+//!     .session_id("session456")
+//!     .build()
+//!     .unwrap();
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::{GameEventsIOEvent, GameEventsIOEventBuilder};
+
+/// Event sent for a purchase
+pub const EVENT_PURCHASE: &str = "purchase";
+
+/// Event sent when a level starts
+pub const EVENT_LEVEL_STARTED: &str = "level_started";
+
+/// Event sent when a level is completed
+pub const EVENT_LEVEL_COMPLETED: &str = "level_completed";
+
+/// Event sent when a level is failed
+pub const EVENT_LEVEL_FAILED: &str = "level_failed";
+
+/// Event sent when an ad is shown
+pub const EVENT_AD_IMPRESSION: &str = "ad_impression";
+
+/// Event sent on a progression step
+pub const EVENT_PROGRESSION: &str = "progression";
+
+/// Property keys of the standard events
+pub mod keys {
+    /// Purchased item
+    pub const ITEM_ID: &str = "item_id";
+    /// Price in `CURRENCY`
+    pub const PRICE: &str = "price";
+    /// ISO 4217 currency code
+    pub const CURRENCY: &str = "currency";
+    /// Level identifier
+    pub const LEVEL_ID: &str = "level_id";
+    /// Final score of a level
+    pub const SCORE: &str = "score";
+    /// Time spent, in milliseconds
+    pub const DURATION_MS: &str = "duration_ms";
+    /// Why a level was failed
+    pub const REASON: &str = "reason";
+    /// Ad network that served the ad
+    pub const AD_NETWORK: &str = "ad_network";
+    /// Placement the ad was shown in
+    pub const AD_PLACEMENT: &str = "ad_placement";
+    /// Revenue reported for the impression, in USD
+    pub const AD_REVENUE: &str = "ad_revenue";
+    /// Progression step, e.g. "world_1/tutorial"
+    pub const PROGRESSION_STEP: &str = "progression_step";
+    /// `ProgressionStatus` of the step
+    pub const PROGRESSION_STATUS: &str = "progression_status";
+}
+
+/// Status of a progression step
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressionStatus {
+    /// The player started the step
+    Started,
+
+    /// The player completed the step
+    Completed,
+
+    /// The player failed the step
+    Failed,
+}
+
+impl ProgressionStatus {
+    /// Value sent in the `progression_status` property
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProgressionStatus::Started => "started",
+            ProgressionStatus::Completed => "completed",
+            ProgressionStatus::Failed => "failed",
+        }
+    }
+}
+
+fn standard_event<const N: usize>(
+    name: &str,
+    properties: [(&str, Value); N],
+) -> GameEventsIOEventBuilder {
+    let properties: HashMap<String, Value> = properties
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+    let mut builder = GameEventsIOEventBuilder::default();
+    builder.event(name).event_properties(properties);
+    builder
+}
+
+impl GameEventsIOEvent {
+    /// A purchase of `item_id` for `price` in `currency` (ISO 4217, e.g. "USD")
+    pub fn purchase(
+        item_id: impl Into<String>,
+        price: f64,
+        currency: impl Into<String>,
+    ) -> GameEventsIOEventBuilder {
+        standard_event(
+            EVENT_PURCHASE,
+            [
+                (keys::ITEM_ID, json!(item_id.into())),
+                (keys::PRICE, json!(price)),
+                (keys::CURRENCY, json!(currency.into())),
+            ],
+        )
+    }
+
+    /// The player started a level
+    pub fn level_started(level: impl Into<Value>) -> GameEventsIOEventBuilder {
+        standard_event(EVENT_LEVEL_STARTED, [(keys::LEVEL_ID, level.into())])
+    }
+
+    /// The player completed a level
+    pub fn level_completed(
+        level: impl Into<Value>,
+        score: i64,
+        duration: Duration,
+    ) -> GameEventsIOEventBuilder {
+        standard_event(
+            EVENT_LEVEL_COMPLETED,
+            [
+                (keys::LEVEL_ID, level.into()),
+                (keys::SCORE, json!(score)),
+                (keys::DURATION_MS, json!(duration.as_millis() as u64)),
+            ],
+        )
+    }
+
+    /// The player failed a level
+    pub fn level_failed(
+        level: impl Into<Value>,
+        reason: impl Into<String>,
+        duration: Duration,
+    ) -> GameEventsIOEventBuilder {
+        standard_event(
+            EVENT_LEVEL_FAILED,
+            [
+                (keys::LEVEL_ID, level.into()),
+                (keys::REASON, json!(reason.into())),
+                (keys::DURATION_MS, json!(duration.as_millis() as u64)),
+            ],
+        )
+    }
+
+    /// An ad was shown; `revenue` is in USD as reported by the network
+    pub fn ad_impression(
+        network: impl Into<String>,
+        placement: impl Into<String>,
+        revenue: f64,
+    ) -> GameEventsIOEventBuilder {
+        standard_event(
+            EVENT_AD_IMPRESSION,
+            [
+                (keys::AD_NETWORK, json!(network.into())),
+                (keys::AD_PLACEMENT, json!(placement.into())),
+                (keys::AD_REVENUE, json!(revenue)),
+            ],
+        )
+    }
+
+    /// The player reached a progression step
+    pub fn progression(
+        step: impl Into<String>,
+        status: ProgressionStatus,
+    ) -> GameEventsIOEventBuilder {
+        standard_event(
+            EVENT_PROGRESSION,
+            [
+                (keys::PROGRESSION_STEP, json!(step.into())),
+                (keys::PROGRESSION_STATUS, json!(status.as_str())),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_events() {
+        let event = GameEventsIOEvent::purchase("sword_legendary", 9.99, "USD")
+            .user_id("user123")
+            .session_id("session456")
+            .build()
+            .unwrap();
+        assert_eq!(event.event, "purchase");
+        assert_eq!(event.event_properties[keys::ITEM_ID], "sword_legendary");
+        assert_eq!(event.event_properties[keys::PRICE], 9.99);
+        assert_eq!(event.event_properties[keys::CURRENCY], "USD");
+
+        let event = GameEventsIOEvent::level_completed(5, 1500, Duration::from_secs(90))
+            .build()
+            .unwrap();
+        assert_eq!(event.event_properties[keys::LEVEL_ID], 5);
+        assert_eq!(event.event_properties[keys::DURATION_MS], 90_000);
+
+        let event = GameEventsIOEvent::progression("world_1/tutorial", ProgressionStatus::Failed)
+            .build()
+            .unwrap();
+        assert_eq!(event.event_properties[keys::PROGRESSION_STATUS], "failed");
+    }
+}
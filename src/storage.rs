@@ -0,0 +1,249 @@
+//! On-disk SDK state.
+//!
+//! Everything the SDK persists lives in a single storage directory chosen
+//! by the game (see `GameEventsIOClientBuilder::storage_dir`).
+//!
+//! JSON state files are wrapped in a `{"version": N, "data": ...}` envelope.
+//! Each `StateFile` lists the migrations from its first version to the
+//! current one, so files written by any older SDK are upgraded on load
+//! instead of being discarded. Files written before envelopes existed are
+//! read as version 1.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Marker file recording that the player opted out of tracking
+pub(crate) const OPT_OUT_FILE: &str = "opt_out";
+
+/// Per-day dedupe state of `UniqueTracker`
+pub(crate) const UNIQUE_DAILY_FILE: &str = "unique_daily.json";
+
+/// Purchase transaction ids already logged
+pub(crate) const PURCHASE_IDS_FILE: &str = "purchase_ids.json";
+
+/// Session resumed by `SessionManager` after a quick relaunch
+pub(crate) const LAST_SESSION_FILE: &str = "last_session.json";
+
+/// Anonymous user id created by `identity::anonymous_id`
+pub(crate) const ANONYMOUS_ID_FILE: &str = "anonymous_id.json";
+
+/// Events the backend refused, one JSON event per line
+pub(crate) const DEAD_LETTERS_FILE: &str = "dead_letters.jsonl";
+
+/// Files removed when the player opts out
+pub(crate) const PURGED_ON_OPT_OUT: &[&str] = &[
+    UNIQUE_DAILY_FILE,
+    PURCHASE_IDS_FILE,
+    LAST_SESSION_FILE,
+    ANONYMOUS_ID_FILE,
+    DEAD_LETTERS_FILE,
+];
+
+/// Upgrade the data of a state file by one version
+pub(crate) type Migration = fn(Value) -> io::Result<Value>;
+
+/// Versioned JSON state file in the storage directory
+pub(crate) struct StateFile {
+    /// File name inside the storage directory
+    pub(crate) name: &'static str,
+
+    /// `migrations[i]` upgrades data from version `i + 1` to `i + 2`
+    pub(crate) migrations: &'static [Migration],
+}
+
+/// `UniqueTracker` state
+pub(crate) const UNIQUE_DAILY: StateFile = StateFile {
+    name: UNIQUE_DAILY_FILE,
+    migrations: &[],
+};
+
+/// `log_purchase` dedupe state
+pub(crate) const PURCHASE_IDS: StateFile = StateFile {
+    name: PURCHASE_IDS_FILE,
+    migrations: &[],
+};
+
+/// `SessionManager` state
+pub(crate) const LAST_SESSION: StateFile = StateFile {
+    name: LAST_SESSION_FILE,
+    migrations: &[],
+};
+
+/// Persisted anonymous user id
+pub(crate) const ANONYMOUS_ID: StateFile = StateFile {
+    name: ANONYMOUS_ID_FILE,
+    migrations: &[],
+};
+
+impl StateFile {
+    /// Version written by this SDK
+    pub(crate) fn version(&self) -> u64 {
+        1 + self.migrations.len() as u64
+    }
+
+    /// Load and migrate the file, `None` if it does not exist
+    ///
+    /// Files from a newer SDK are rejected rather than misread, and are left
+    /// untouched on disk.
+    pub(crate) fn load<T: DeserializeOwned>(&self, dir: &Path) -> io::Result<Option<T>> {
+        let contents = match fs::read(dir.join(self.name)) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let (version, mut data) = unwrap_envelope(serde_json::from_slice(&contents)?);
+        if version == 0 || version > self.version() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} has version {}, this SDK supports up to {}",
+                    self.name,
+                    version,
+                    self.version()
+                ),
+            ));
+        }
+        for migrate in &self.migrations[(version - 1) as usize..] {
+            data = migrate(data)?;
+        }
+
+        Ok(Some(serde_json::from_value(data)?))
+    }
+
+    /// Write the file at the current version
+    ///
+    /// The data goes to a temporary file first, so a crash mid-write never
+    /// leaves a truncated file behind.
+    pub(crate) fn save<T: Serialize>(&self, dir: &Path, data: &T) -> io::Result<()> {
+        let envelope = serde_json::json!({
+            "version": self.version(),
+            "data": data,
+        });
+        fs::create_dir_all(dir)?;
+        let path = dir.join(self.name);
+        let tmp = dir.join(format!("{}.tmp", self.name));
+        fs::write(&tmp, serde_json::to_vec(&envelope)?)?;
+        fs::rename(tmp, path)
+    }
+}
+
+/// Split a file into its version and data, treating unwrapped files as version 1
+fn unwrap_envelope(value: Value) -> (u64, Value) {
+    match value {
+        Value::Object(mut map)
+            if map.len() == 2
+                && map.contains_key("data")
+                && map.get("version").is_some_and(Value::is_u64) =>
+        {
+            let version = map["version"].as_u64().unwrap_or_default();
+            (version, map.remove("data").unwrap_or_default())
+        }
+        value => (1, value),
+    }
+}
+
+/// Check whether a marker file exists
+pub(crate) fn flag_exists(dir: &Path, name: &str) -> bool {
+    dir.join(name).is_file()
+}
+
+/// Create a marker file, creating the storage directory if needed
+pub(crate) fn set_flag(dir: &Path, name: &str) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(name), b"1")
+}
+
+/// Remove a marker file if it exists
+pub(crate) fn clear_flag(dir: &Path, name: &str) -> io::Result<()> {
+    remove_if_exists(&dir.join(name))
+}
+
+/// Remove every file listed in `PURGED_ON_OPT_OUT`
+pub(crate) fn purge(dir: &Path) -> io::Result<()> {
+    for name in PURGED_ON_OPT_OUT {
+        remove_if_exists(&dir.join(name))?;
+    }
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rename_count(data: Value) -> io::Result<Value> {
+        let mut map = match data {
+            Value::Object(map) => map,
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "not an object")),
+        };
+        let count = map.remove("count").unwrap_or_default();
+        map.insert("total".to_string(), count);
+        Ok(Value::Object(map))
+    }
+
+    fn add_label(mut data: Value) -> io::Result<Value> {
+        data["label"] = json!("migrated");
+        Ok(data)
+    }
+
+    const TEST_FILE: StateFile = StateFile {
+        name: "test_state.json",
+        migrations: &[rename_count, add_label],
+    };
+
+    #[test]
+    fn test_state_file_migrates_old_versions() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(TEST_FILE.name);
+
+        // Unversioned file from before envelopes
+        fs::write(&path, br#"{"count": 3}"#).unwrap();
+        let data: Value = TEST_FILE.load(&dir).unwrap().unwrap();
+        assert_eq!(data, json!({"total": 3, "label": "migrated"}));
+
+        // Version 2 only needs the last migration
+        fs::write(&path, br#"{"version": 2, "data": {"total": 5}}"#).unwrap();
+        let data: Value = TEST_FILE.load(&dir).unwrap().unwrap();
+        assert_eq!(data, json!({"total": 5, "label": "migrated"}));
+
+        // Round trip at the current version
+        TEST_FILE.save(&dir, &data).unwrap();
+        let saved: Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(saved["version"], 3);
+        assert_eq!(TEST_FILE.load::<Value>(&dir).unwrap().unwrap(), data);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_state_file_rejects_newer_versions() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(TEST_FILE.name);
+        let newer = br#"{"version": 9, "data": {"total": 1}}"#;
+        fs::write(&path, newer).unwrap();
+
+        assert!(TEST_FILE.load::<Value>(&dir).is_err());
+        assert!(TEST_FILE
+            .load::<Value>(&dir.join("missing"))
+            .unwrap()
+            .is_none());
+        assert_eq!(fs::read(&path).unwrap(), newer);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,280 @@
+//! Event storm detection.
+//!
+//! A storm is the same event name being logged more than `max_per_second`
+//! times per second for `sustained_secs` consecutive seconds. While a storm
+//! is active the offending events are counted instead of buffered, and a
+//! single `event_storm_detected` summary is emitted once it subsides.
+
+use std::collections::HashMap;
+
+use crate::{diagnostics, GameEventsIOEvent, GameEventsIOEventBuilder};
+
+/// Name of the summary event emitted when a storm subsides
+pub const EVENT_STORM_DETECTED: &str = "event_storm_detected";
+
+/// Thresholds that define an event storm
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StormGuardConfig {
+    /// Maximum number of events with the same name per second
+    pub max_per_second: u32,
+
+    /// Consecutive seconds over the limit before a storm is declared
+    pub sustained_secs: u32,
+}
+
+impl StormGuardConfig {
+    /// Create a config with the given thresholds
+    pub fn new(max_per_second: u32, sustained_secs: u32) -> Self {
+        Self {
+            max_per_second,
+            sustained_secs: sustained_secs.max(1),
+        }
+    }
+}
+
+impl Default for StormGuardConfig {
+    fn default() -> Self {
+        Self::new(100, 3)
+    }
+}
+
+/// Circuit breaker that collapses event storms into summary events
+#[derive(Clone, Debug, Default)]
+pub struct StormGuard {
+    config: StormGuardConfig,
+    names: HashMap<String, NameState>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct NameState {
+    /// Second currently being counted
+    second: u64,
+
+    /// Events seen during `second`
+    count: u32,
+
+    /// Consecutive over-limit seconds immediately before `second`
+    hot_seconds: u32,
+
+    /// Active storm, if any
+    storm: Option<Storm>,
+}
+
+#[derive(Clone, Debug)]
+struct Storm {
+    started_at: u64,
+    suppressed: u64,
+    user_id: String,
+    session_id: String,
+}
+
+impl From<StormGuardConfig> for StormGuard {
+    fn from(config: StormGuardConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+impl StormGuard {
+    /// Create a storm guard with the given thresholds
+    pub fn new(config: StormGuardConfig) -> Self {
+        Self {
+            config,
+            names: HashMap::new(),
+        }
+    }
+
+    /// Get the configured thresholds
+    pub fn config(&self) -> StormGuardConfig {
+        self.config
+    }
+
+    /// Check whether a storm is currently active for an event name
+    pub fn is_storming(&self, event: &str) -> bool {
+        self.names
+            .get(event)
+            .is_some_and(|state| state.storm.is_some())
+    }
+
+    /// Pass an event through the guard, returning the events to buffer
+    ///
+    /// The result is empty while the event's name is storming, and may
+    /// contain a summary event when a previous storm has just ended.
+    pub fn admit(&mut self, event: GameEventsIOEvent) -> Vec<GameEventsIOEvent> {
+        let config = self.config;
+        let state = self.names.entry(event.event.clone()).or_default();
+        let mut admitted = Vec::new();
+
+        if event.time != state.second {
+            admitted.extend(state.roll(&event.event, event.time, &config));
+        }
+
+        state.count = state.count.saturating_add(1);
+        if state.storm.is_none()
+            && state.count > config.max_per_second
+            && state.hot_seconds + 1 >= config.sustained_secs
+        {
+            state.storm = Some(Storm {
+                started_at: event.time,
+                suppressed: 0,
+                user_id: String::new(),
+                session_id: String::new(),
+            });
+        }
+
+        match state.storm.as_mut() {
+            Some(storm) => {
+                storm.suppressed += 1;
+                storm.user_id = event.user_id;
+                storm.session_id = event.session_id;
+            }
+            None => admitted.push(event),
+        }
+
+        admitted
+    }
+
+    /// Close out storms that have gone quiet by `now` (Unix seconds)
+    pub fn settle(&mut self, now: u64) -> Vec<GameEventsIOEvent> {
+        let config = self.config;
+        let mut summaries = Vec::new();
+
+        for (name, state) in self.names.iter_mut() {
+            if now > state.second {
+                summaries.extend(state.roll(name, now, &config));
+            }
+        }
+
+        // Forget names that have nothing left to track
+        self.names
+            .retain(|_, state| state.count > 0 || state.hot_seconds > 0 || state.storm.is_some());
+
+        summaries
+    }
+}
+
+impl NameState {
+    /// Finish counting the current second and start counting `now`
+    fn roll(
+        &mut self,
+        name: &str,
+        now: u64,
+        config: &StormGuardConfig,
+    ) -> Option<GameEventsIOEvent> {
+        let over = self.count > config.max_per_second;
+        self.hot_seconds = if over && now == self.second + 1 {
+            self.hot_seconds + 1
+        } else {
+            0
+        };
+        self.second = now;
+        self.count = 0;
+
+        if self.hot_seconds > 0 {
+            return None;
+        }
+
+        let storm = self.storm.take()?;
+        let mut props = HashMap::new();
+        props.insert("event_name".to_string(), serde_json::json!(name));
+        props.insert("count".to_string(), serde_json::json!(storm.suppressed));
+        props.insert(
+            "started_at".to_string(),
+            serde_json::json!(storm.started_at),
+        );
+        props.insert(
+            "duration_secs".to_string(),
+            serde_json::json!(now.saturating_sub(storm.started_at)),
+        );
+
+        Some(diagnostics::recover(
+            GameEventsIOEventBuilder::default()
+                .event(EVENT_STORM_DETECTED)
+                .user_id(storm.user_id)
+                .session_id(storm.session_id)
+                .time(now)
+                .event_properties(props)
+                .build(),
+            "Failed to build storm summary event",
+            GameEventsIOEvent::default,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event_at(name: &str, time: u64) -> GameEventsIOEvent {
+        GameEventsIOEventBuilder::default()
+            .event(name)
+            .user_id("user123")
+            .session_id("session456")
+            .time(time)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_below_threshold_passes_through() {
+        let mut guard = StormGuard::new(StormGuardConfig::new(5, 2));
+
+        let mut admitted = 0;
+        for second in 100..110 {
+            for _ in 0..5 {
+                admitted += guard.admit(event_at("shot_fired", second)).len();
+            }
+        }
+
+        assert_eq!(admitted, 50);
+        assert!(!guard.is_storming("shot_fired"));
+    }
+
+    #[test]
+    fn test_storm_collapses_into_summary() {
+        let mut guard = StormGuard::new(StormGuardConfig::new(5, 2));
+
+        let mut admitted = Vec::new();
+        for second in 100..104 {
+            for _ in 0..10 {
+                admitted.extend(guard.admit(event_at("shot_fired", second)));
+            }
+        }
+
+        // First second fully passes, second passes up to the limit
+        assert_eq!(admitted.len(), 15);
+        assert!(guard.is_storming("shot_fired"));
+
+        // A quiet second ends the storm
+        admitted.extend(guard.admit(event_at("shot_fired", 105)));
+        assert!(!guard.is_storming("shot_fired"));
+
+        let summary = &admitted[15];
+        assert_eq!(summary.event, EVENT_STORM_DETECTED);
+        assert_eq!(summary.user_id, "user123");
+        assert_eq!(
+            summary.event_properties.get("event_name").unwrap(),
+            "shot_fired"
+        );
+        assert_eq!(summary.event_properties.get("count").unwrap(), 25);
+
+        // Normal logging is restored
+        assert_eq!(admitted[16].event, "shot_fired");
+        assert_eq!(admitted.len(), 17);
+    }
+
+    #[test]
+    fn test_settle_ends_idle_storm() {
+        let mut guard = StormGuard::new(StormGuardConfig::new(1, 1));
+
+        for _ in 0..3 {
+            guard.admit(event_at("hitch", 50));
+        }
+        assert!(guard.is_storming("hitch"));
+
+        assert!(guard.settle(50).is_empty());
+        let summaries = guard.settle(52);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].event_properties.get("count").unwrap(), 2);
+        assert!(!guard.is_storming("hitch"));
+    }
+}
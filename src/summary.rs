@@ -0,0 +1,206 @@
+//! Aggregate `session_summary` event.
+//!
+//! With `GameEventsIOSessionBuilder::emit_summary`, the session counts the
+//! events pushed to it and, when the session ends, records one
+//! `session_summary` event with the aggregates just before `session_end`.
+//! The summary is critical, so it is neither rate limited nor affected by
+//! the sampling of the detailed events it counts. Micro events are not
+//! counted.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::{json, Value};
+
+use crate::standard::{
+    self, EVENT_AD_IMPRESSION, EVENT_LEVEL_COMPLETED, EVENT_LEVEL_FAILED, EVENT_LEVEL_STARTED,
+    EVENT_PROGRESSION, EVENT_PURCHASE,
+};
+use crate::GameEventsIOEvent;
+
+/// Event recorded when a session ends, with the aggregates of the session
+pub const EVENT_SESSION_SUMMARY: &str = "session_summary";
+
+/// Event property that puts an event in a category of the summary
+///
+/// Events without it are categorized by name: `progression`, `monetization`,
+/// `ads` or `other`.
+pub const CATEGORY_PROPERTY: &str = "category";
+
+/// Property keys of `session_summary`
+pub mod keys {
+    /// Session length in seconds
+    pub const PLAYTIME: &str = "playtime";
+    /// Number of events in the session
+    pub const EVENT_COUNT: &str = "event_count";
+    /// Object of category to number of events
+    pub const EVENTS_BY_CATEGORY: &str = "events_by_category";
+    /// Number of `level_started` events
+    pub const LEVELS_ATTEMPTED: &str = "levels_attempted";
+    /// Number of `level_completed` events
+    pub const LEVELS_COMPLETED: &str = "levels_completed";
+    /// Number of `level_failed` events
+    pub const LEVELS_FAILED: &str = "levels_failed";
+    /// Object of currency to purchase revenue
+    pub const REVENUE: &str = "revenue";
+    /// Ad revenue in USD
+    pub const AD_REVENUE: &str = "ad_revenue";
+}
+
+/// Aggregates of the events pushed to a session so far
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionSummary {
+    /// Number of events per category
+    pub events_by_category: BTreeMap<String, u64>,
+
+    /// Number of levels started
+    pub levels_attempted: u64,
+
+    /// Number of levels completed
+    pub levels_completed: u64,
+
+    /// Number of levels failed
+    pub levels_failed: u64,
+
+    /// Purchase revenue per currency
+    pub revenue: BTreeMap<String, f64>,
+
+    /// Ad revenue in USD
+    pub ad_revenue: f64,
+}
+
+impl SessionSummary {
+    /// Total number of events counted
+    pub fn event_count(&self) -> u64 {
+        self.events_by_category.values().sum()
+    }
+
+    /// Add an event to the aggregates
+    pub(crate) fn record(&mut self, event: &GameEventsIOEvent) {
+        let properties = &event.event_properties;
+        *self
+            .events_by_category
+            .entry(category(event).to_string())
+            .or_default() += 1;
+
+        match event.event.as_str() {
+            EVENT_LEVEL_STARTED => self.levels_attempted += 1,
+            EVENT_LEVEL_COMPLETED => self.levels_completed += 1,
+            EVENT_LEVEL_FAILED => self.levels_failed += 1,
+            EVENT_PURCHASE => {
+                let price = properties.get(standard::keys::PRICE);
+                let currency = properties.get(standard::keys::CURRENCY);
+                if let (Some(price), Some(currency)) = (
+                    price.and_then(Value::as_f64),
+                    currency.and_then(Value::as_str),
+                ) {
+                    *self.revenue.entry(currency.to_string()).or_default() += price;
+                }
+            }
+            EVENT_AD_IMPRESSION => {
+                self.ad_revenue += properties
+                    .get(standard::keys::AD_REVENUE)
+                    .and_then(Value::as_f64)
+                    .unwrap_or_default();
+            }
+            _ => {}
+        }
+    }
+
+    /// Properties of the `session_summary` event for a session of `playtime` seconds
+    pub(crate) fn properties(&self, playtime: u64) -> HashMap<String, Value> {
+        HashMap::from([
+            (keys::PLAYTIME.to_string(), json!(playtime)),
+            (keys::EVENT_COUNT.to_string(), json!(self.event_count())),
+            (
+                keys::EVENTS_BY_CATEGORY.to_string(),
+                json!(self.events_by_category),
+            ),
+            (
+                keys::LEVELS_ATTEMPTED.to_string(),
+                json!(self.levels_attempted),
+            ),
+            (
+                keys::LEVELS_COMPLETED.to_string(),
+                json!(self.levels_completed),
+            ),
+            (keys::LEVELS_FAILED.to_string(), json!(self.levels_failed)),
+            (keys::REVENUE.to_string(), json!(self.revenue)),
+            (keys::AD_REVENUE.to_string(), json!(self.ad_revenue)),
+        ])
+    }
+}
+
+fn category(event: &GameEventsIOEvent) -> &str {
+    if let Some(category) = event
+        .event_properties
+        .get(CATEGORY_PROPERTY)
+        .and_then(Value::as_str)
+    {
+        return category;
+    }
+    match event.event.as_str() {
+        EVENT_LEVEL_STARTED | EVENT_LEVEL_COMPLETED | EVENT_LEVEL_FAILED | EVENT_PROGRESSION => {
+            "progression"
+        }
+        EVENT_PURCHASE => "monetization",
+        EVENT_AD_IMPRESSION => "ads",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventPriority, GameEventsIOSessionBuilder, EVENT_SESSION_END};
+
+    #[test]
+    fn test_summary_on_session_end() {
+        let session = GameEventsIOSessionBuilder::default()
+            .user_id("user123")
+            .emit_summary()
+            .build()
+            .unwrap();
+
+        let level = |id: u64| HashMap::from([(standard::keys::LEVEL_ID.to_string(), json!(id))]);
+        session.push_event(EVENT_LEVEL_STARTED, level(1));
+        session.push_event(EVENT_LEVEL_FAILED, level(1));
+        session.push_event(EVENT_LEVEL_STARTED, level(1));
+        session.push_event(EVENT_LEVEL_COMPLETED, level(1));
+        session.push_event(
+            EVENT_PURCHASE,
+            HashMap::from([
+                (standard::keys::PRICE.to_string(), json!(4.99)),
+                (standard::keys::CURRENCY.to_string(), json!("USD")),
+            ]),
+        );
+        session.push_event(
+            "emote_used",
+            HashMap::from([(CATEGORY_PROPERTY.to_string(), json!("social"))]),
+        );
+        session.push_event("settings_opened", HashMap::new());
+        assert_eq!(session.summary().unwrap().event_count(), 7);
+
+        session.end_session();
+        let events = session.take_events(100);
+        let names: Vec<_> = events.iter().map(|e| e.event.as_str()).collect();
+        assert_eq!(
+            names[names.len() - 2..],
+            [EVENT_SESSION_SUMMARY, EVENT_SESSION_END]
+        );
+
+        let summary = &events[events.len() - 2];
+        assert_eq!(summary.priority(), EventPriority::Critical);
+        let properties = &summary.event_properties;
+        assert_eq!(properties[keys::LEVELS_ATTEMPTED], 2);
+        assert_eq!(properties[keys::LEVELS_COMPLETED], 1);
+        assert_eq!(properties[keys::REVENUE], json!({ "USD": 4.99 }));
+        assert_eq!(
+            properties[keys::EVENTS_BY_CATEGORY],
+            json!({ "progression": 4, "monetization": 1, "social": 1, "other": 1 })
+        );
+
+        // The next session starts counting from zero
+        session.push_event("settings_opened", HashMap::new());
+        assert_eq!(session.summary().unwrap().event_count(), 1);
+    }
+}
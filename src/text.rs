@@ -0,0 +1,136 @@
+//! Cleanup of string properties.
+//!
+//! Player-entered text (names, chat, guild tags) arrives in any script and
+//! sometimes with stray control characters or decomposed accents. A
+//! `StringGuard` normalizes it to NFC, strips control characters and
+//! truncates it to a number of grapheme clusters, so a cut never splits a
+//! character, a Hangul syllable or an emoji sequence.
+
+use serde_json::Value;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::GameEventsIOEvent;
+
+/// Rules applied to every string property of logged events
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StringGuard {
+    normalize: bool,
+    strip_control: bool,
+    max_graphemes: Option<usize>,
+}
+
+impl Default for StringGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StringGuard {
+    /// Normalize to NFC and strip control characters, without a length limit
+    pub fn new() -> Self {
+        Self {
+            normalize: true,
+            strip_control: true,
+            max_graphemes: None,
+        }
+    }
+
+    /// Whether to normalize strings to NFC (default: true)
+    pub fn normalize(mut self, enabled: bool) -> Self {
+        self.normalize = enabled;
+        self
+    }
+
+    /// Whether to remove control characters such as `\0` or `\n` (default: true)
+    pub fn strip_control(mut self, enabled: bool) -> Self {
+        self.strip_control = enabled;
+        self
+    }
+
+    /// Keep at most `max` grapheme clusters (user-perceived characters)
+    pub fn max_graphemes(mut self, max: usize) -> Self {
+        self.max_graphemes = Some(max);
+        self
+    }
+
+    /// Clean a string
+    pub fn clean(&self, text: &str) -> String {
+        let mut text = if self.normalize {
+            text.nfc().collect()
+        } else {
+            text.to_string()
+        };
+        if self.strip_control {
+            text.retain(|c| !c.is_control());
+        }
+        if let Some(max) = self.max_graphemes {
+            if let Some((end, _)) = text.grapheme_indices(true).nth(max) {
+                text.truncate(end);
+            }
+        }
+        text
+    }
+
+    /// Clean raw bytes from outside Rust, replacing invalid UTF-8 with U+FFFD
+    pub fn clean_bytes(&self, bytes: &[u8]) -> String {
+        self.clean(&String::from_utf8_lossy(bytes))
+    }
+
+    /// Clean all string values in a JSON value, including nested ones
+    pub fn clean_value(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.clean(text),
+            Value::Array(values) => values.iter_mut().for_each(|value| self.clean_value(value)),
+            Value::Object(values) => values
+                .values_mut()
+                .for_each(|value| self.clean_value(value)),
+            _ => {}
+        }
+    }
+
+    /// Clean the event and user properties of an event
+    pub fn apply(&self, event: &mut GameEventsIOEvent) {
+        for value in event
+            .event_properties
+            .values_mut()
+            .chain(event.user_properties.values_mut())
+        {
+            self.clean_value(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_clean_strings() {
+        let guard = StringGuard::new().max_graphemes(3);
+
+        // Decomposed "é" and Hangul jamo are composed before counting
+        assert_eq!(guard.clean("e\u{301}a\u{0}b\ncd"), "éab");
+        assert_eq!(guard.clean("\u{1112}\u{1161}\u{11AB}글날개"), "한글날");
+
+        // Emoji sequences and CJK text are never split
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        assert_eq!(
+            guard.clean(&format!("{}{}", family, "龍之谷")),
+            format!("{}龍之", family)
+        );
+        assert_eq!(StringGuard::new().clean_bytes(b"ok\xFF"), "ok\u{FFFD}");
+
+        let mut event = GameEventsIOEvent::default();
+        event
+            .event_properties
+            .insert("names".to_string(), json!(["プレイヤー名前", 5]));
+        event
+            .user_properties
+            .insert("guild".to_string(), json!("\u{7}abc"));
+        guard.apply(&mut event);
+        assert_eq!(event.event_properties["names"], json!(["プレイ", 5]));
+        assert_eq!(event.user_properties["guild"], "abc");
+    }
+}
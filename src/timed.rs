@@ -0,0 +1,81 @@
+//! Timed events.
+//!
+//! `start_timed_event` starts a stopwatch for an event name, and
+//! `finish_timed_event` pushes the event with the elapsed time in the
+//! standard `duration_ms` property. Timers use a monotonic clock, so
+//! changes to the system clock don't affect durations.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::standard::keys;
+use crate::{lock, GameEventsIOSession};
+
+impl GameEventsIOSession {
+    /// Start timing an event; restarts the timer if it is already running
+    pub fn start_timed_event(&self, event: impl Into<String>) {
+        lock(&self.timers).insert(event.into(), Instant::now());
+    }
+
+    /// Push an event with the time since `start_timed_event` in `duration_ms`
+    ///
+    /// Returns the duration. An event that wasn't started is still pushed,
+    /// without `duration_ms`, and `None` is returned.
+    pub fn finish_timed_event(
+        &self,
+        event: &str,
+        mut event_properties: HashMap<String, Value>,
+    ) -> Option<Duration> {
+        let duration = lock(&self.timers)
+            .remove(event)
+            .map(|start| start.elapsed());
+        if let Some(duration) = duration {
+            event_properties.insert(
+                keys::DURATION_MS.to_string(),
+                json!(duration.as_millis() as u64),
+            );
+        }
+        self.push_event(event, event_properties);
+        duration
+    }
+
+    /// Stop timing an event without pushing it
+    pub fn cancel_timed_event(&self, event: &str) {
+        lock(&self.timers).remove(event);
+    }
+
+    /// Whether a timer is running for the event
+    pub fn is_timing(&self, event: &str) -> bool {
+        lock(&self.timers).contains_key(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timed_event_records_duration() {
+        let session = GameEventsIOSession::new("user123", "session456");
+        session.start_timed_event("level_5");
+        session.start_timed_event("cutscene");
+        std::thread::sleep(Duration::from_millis(20));
+        session.cancel_timed_event("cutscene");
+
+        let properties = HashMap::from([("stars".to_string(), json!(3))]);
+        let duration = session.finish_timed_event("level_5", properties).unwrap();
+        assert!(duration >= Duration::from_millis(20));
+        assert!(!session.is_timing("level_5"));
+        assert!(session
+            .finish_timed_event("cutscene", HashMap::new())
+            .is_none());
+
+        let events = session.take_events(10);
+        let level = &events[1].event_properties;
+        assert_eq!(level["stars"], 3);
+        assert!(level[keys::DURATION_MS].as_u64().unwrap() >= 20);
+        assert!(!events[2].event_properties.contains_key(keys::DURATION_MS));
+    }
+}
@@ -0,0 +1,672 @@
+//! Delivery of event batches to a backend.
+//!
+//! `GameEventsIOClient` sends through any `Transport`. Besides HTTP, the SDK
+//! ships `MemoryTransport` for unit tests and `FileTransport` for CI runs
+//! that should not touch the network.
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::{
+    dictionary, ndjson, Compression, Compressor, GameEventsIOError, GameEventsIOEvent,
+    NetworkConfig, WireVersion,
+};
+
+/// Response returned by a transport for one batch
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransportResponse {
+    /// HTTP status code (or the closest equivalent for non-HTTP transports)
+    pub status: u16,
+
+    /// Response body
+    pub body: String,
+
+    /// How long the backend asked to wait before retrying (`Retry-After`)
+    pub retry_after: Option<Duration>,
+
+    /// Backend clock as a Unix timestamp in seconds (`Date`), if reported
+    pub server_time: Option<u64>,
+}
+
+/// Outcome of a successfully delivered batch
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FlushResult {
+    /// Number of events the backend accepted
+    pub accepted: usize,
+
+    /// Number of events the backend rejected
+    pub rejected: usize,
+
+    /// Positions of the rejected events in the batch, if reported
+    pub rejected_indices: Vec<usize>,
+
+    /// Raw response body
+    pub body: String,
+}
+
+/// Counts reported by the ingestion API, all optional
+#[derive(Default, Deserialize)]
+struct IngestReport {
+    accepted: Option<usize>,
+    rejected: Option<usize>,
+    #[serde(default)]
+    rejected_indices: Vec<usize>,
+}
+
+impl FlushResult {
+    /// Interpret a transport response for a batch of `batch_len` events
+    pub(crate) fn from_response(
+        response: TransportResponse,
+        batch_len: usize,
+    ) -> Result<Self, GameEventsIOError> {
+        let report: IngestReport = serde_json::from_str(&response.body).unwrap_or_default();
+        match response.status {
+            200..=299 => {
+                let rejected = report.rejected.unwrap_or(report.rejected_indices.len());
+                Ok(FlushResult {
+                    accepted: report
+                        .accepted
+                        .unwrap_or(batch_len.saturating_sub(rejected)),
+                    rejected,
+                    rejected_indices: report.rejected_indices,
+                    body: response.body,
+                })
+            }
+            401 | 403 => Err(GameEventsIOError::Unauthorized),
+            413 => Err(GameEventsIOError::PayloadTooLarge),
+            422 => {
+                // Without details the whole batch counts as rejected
+                let rejected_indices = if report.rejected_indices.is_empty() {
+                    (0..batch_len).collect()
+                } else {
+                    report.rejected_indices
+                };
+                Err(GameEventsIOError::InvalidEvents { rejected_indices })
+            }
+            429 => Err(GameEventsIOError::RateLimited {
+                retry_after: response.retry_after,
+            }),
+            status => Err(GameEventsIOError::Server {
+                status,
+                body: response.body,
+            }),
+        }
+    }
+}
+
+/// Sends batches of events somewhere
+pub trait Transport: Send + Sync {
+    /// Send one batch of events
+    fn send(&self, batch: &[GameEventsIOEvent]) -> Result<TransportResponse, GameEventsIOError>;
+
+    /// Send one batch of events, giving up after `timeout`
+    ///
+    /// Used by `GameEventsIOClient::shutdown` to stay within its deadline.
+    /// The default ignores the timeout, which suits transports that don't
+    /// block on the network.
+    fn send_within(
+        &self,
+        batch: &[GameEventsIOEvent],
+        timeout: Duration,
+    ) -> Result<TransportResponse, GameEventsIOError> {
+        let _ = timeout;
+        self.send(batch)
+    }
+
+    /// Send a batch the transport may hold on to while the request is in flight
+    ///
+    /// Used by `GameEventsIOClient`, which takes the batch back afterwards.
+    /// Streaming transports read the events as they upload instead of
+    /// copying the batch. The default lends it to `send` or `send_within`.
+    fn send_shared(
+        &self,
+        batch: Arc<Vec<GameEventsIOEvent>>,
+        timeout: Option<Duration>,
+    ) -> Result<TransportResponse, GameEventsIOError> {
+        match timeout {
+            Some(timeout) => self.send_within(&batch, timeout),
+            None => self.send(&batch),
+        }
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    fn send(&self, batch: &[GameEventsIOEvent]) -> Result<TransportResponse, GameEventsIOError> {
+        (**self).send(batch)
+    }
+
+    fn send_within(
+        &self,
+        batch: &[GameEventsIOEvent],
+        timeout: Duration,
+    ) -> Result<TransportResponse, GameEventsIOError> {
+        (**self).send_within(batch, timeout)
+    }
+
+    fn send_shared(
+        &self,
+        batch: Arc<Vec<GameEventsIOEvent>>,
+        timeout: Option<Duration>,
+    ) -> Result<TransportResponse, GameEventsIOError> {
+        (**self).send_shared(batch, timeout)
+    }
+}
+
+impl fmt::Debug for dyn Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Transport")
+    }
+}
+
+/// Transport posting JSON batches to the game-events.io HTTP API
+#[derive(Clone, Debug)]
+pub struct HttpTransport {
+    url: String,
+    api_key: String,
+    compressor: Arc<dyn Compressor>,
+    dictionary_encoding: bool,
+    ndjson: bool,
+    wire_version: WireVersion,
+    network_config: NetworkConfig,
+    /// `None` if the HTTP client could not be built
+    client: Option<reqwest::blocking::Client>,
+}
+
+impl HttpTransport {
+    /// Create a transport posting to `url` with the given API key
+    pub fn new(url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self::with_network_config(url, api_key, NetworkConfig::default())
+    }
+
+    /// Create a transport with custom TLS, proxy and timeout settings
+    pub fn with_network_config(
+        url: impl Into<String>,
+        api_key: impl Into<String>,
+        network_config: NetworkConfig,
+    ) -> Self {
+        Self {
+            url: url.into(),
+            api_key: api_key.into(),
+            compressor: Arc::new(Compression::None),
+            dictionary_encoding: false,
+            ndjson: false,
+            wire_version: WireVersion::V1,
+            client: network_config.blocking_client(),
+            network_config,
+        }
+    }
+
+    /// Compress request bodies
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compressor = Arc::new(compression);
+        self
+    }
+
+    /// Compress request bodies with a custom codec
+    pub fn compressor(mut self, compressor: impl Compressor + 'static) -> Self {
+        self.compressor = Arc::new(compressor);
+        self
+    }
+
+    /// Send batches in the dictionary envelope (see the `dictionary` module)
+    pub fn dictionary_encoding(mut self, enabled: bool) -> Self {
+        self.dictionary_encoding = enabled;
+        self
+    }
+
+    /// Send batches as NDJSON, streamed if uncompressed (see the `ndjson` module)
+    ///
+    /// Ignored with `dictionary_encoding`.
+    pub fn ndjson(mut self, enabled: bool) -> Self {
+        self.ndjson = enabled;
+        self
+    }
+
+    /// Send payloads in another wire format version (default: V1)
+    ///
+    /// The dictionary envelope always carries V1 payloads.
+    pub fn wire_version(mut self, version: WireVersion) -> Self {
+        self.wire_version = version;
+        self
+    }
+
+    /// Give up on requests that take longer than `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.network_config.timeout = Some(timeout);
+        self.client = self.network_config.blocking_client();
+        self
+    }
+}
+
+/// Read a `Retry-After` header given in seconds
+pub(crate) fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
+/// Read the `Date` header (e.g. "Sun, 06 Nov 1994 08:49:37 GMT") as a Unix timestamp
+pub(crate) fn parse_server_time(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    let date = headers.get(reqwest::header::DATE)?.to_str().ok()?;
+    parse_http_date(date)
+}
+
+fn parse_http_date(date: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let mut parts = date.split_whitespace().skip(1);
+    let day: i64 = parts.next()?.parse().ok()?;
+    let month = parts.next()?;
+    let month = MONTHS.iter().position(|m| *m == month)? as i64 + 1;
+    let year: i64 = parts.next()?.parse().ok()?;
+    let mut time = parts
+        .next()?
+        .split(':')
+        .map(|part| part.parse::<i64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+
+    // Days since 1970-01-01 in the proleptic Gregorian calendar
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    u64::try_from(days * 86_400 + hour * 3600 + minute * 60 + second).ok()
+}
+
+impl HttpTransport {
+    /// `timeout`, or the configured timeout if shorter
+    fn capped_timeout(&self, timeout: Duration) -> Duration {
+        self.network_config
+            .timeout
+            .map_or(timeout, |t| t.min(timeout))
+    }
+
+    /// Post a batch, with a timeout overriding the configured one
+    ///
+    /// Streamed NDJSON bodies read from `shared` if given, and from a copy
+    /// of `batch` otherwise.
+    fn post(
+        &self,
+        batch: &[GameEventsIOEvent],
+        shared: Option<&Arc<Vec<GameEventsIOEvent>>>,
+        timeout: Option<Duration>,
+    ) -> Result<TransportResponse, GameEventsIOError> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| GameEventsIOError::Transport("HTTP client unavailable".to_string()))?;
+        let ndjson = self.ndjson && !self.dictionary_encoding;
+        let mut request = client.post(&self.url);
+        if ndjson && self.compressor.content_encoding().is_none() {
+            let events = shared.cloned().unwrap_or_else(|| Arc::new(batch.to_vec()));
+            let body = ndjson::NdjsonBody::new(events, self.wire_version);
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, ndjson::NDJSON_CONTENT_TYPE)
+                .body(reqwest::blocking::Body::new(body));
+        } else {
+            let (body, content_type) = if self.dictionary_encoding {
+                let envelope = dictionary::encode_batch(batch).map_err(std::io::Error::from)?;
+                (
+                    serde_json::to_vec(&envelope),
+                    dictionary::DICTIONARY_CONTENT_TYPE,
+                )
+            } else if ndjson {
+                (
+                    ndjson::encode_batch(batch, self.wire_version),
+                    ndjson::NDJSON_CONTENT_TYPE,
+                )
+            } else {
+                (self.wire_version.encode_batch(batch), "application/json")
+            };
+            let body = body.map_err(std::io::Error::from)?;
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(self.compressor.compress(&body)?);
+            if let Some(encoding) = self.compressor.content_encoding() {
+                request = request.header(reqwest::header::CONTENT_ENCODING, encoding);
+            }
+        }
+        if !self.api_key.is_empty() {
+            request = request.header("Authorization", format!("Bearer {}", self.api_key));
+        }
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+        let response = request.send()?;
+        let retry_after = parse_retry_after(response.headers());
+        let server_time = parse_server_time(response.headers());
+
+        Ok(TransportResponse {
+            status: response.status().as_u16(),
+            body: response.text()?,
+            retry_after,
+            server_time,
+        })
+    }
+}
+
+impl Transport for HttpTransport {
+    fn send(&self, batch: &[GameEventsIOEvent]) -> Result<TransportResponse, GameEventsIOError> {
+        self.post(batch, None, None)
+    }
+
+    fn send_within(
+        &self,
+        batch: &[GameEventsIOEvent],
+        timeout: Duration,
+    ) -> Result<TransportResponse, GameEventsIOError> {
+        self.post(batch, None, Some(self.capped_timeout(timeout)))
+    }
+
+    fn send_shared(
+        &self,
+        batch: Arc<Vec<GameEventsIOEvent>>,
+        timeout: Option<Duration>,
+    ) -> Result<TransportResponse, GameEventsIOError> {
+        let timeout = timeout.map(|timeout| self.capped_timeout(timeout));
+        self.post(&batch, Some(&batch), timeout)
+    }
+}
+
+/// Transport keeping every batch in memory, for tests
+///
+/// Share it with the client through an `Arc` to inspect what was sent.
+#[derive(Debug)]
+pub struct MemoryTransport {
+    batches: Mutex<Vec<Vec<GameEventsIOEvent>>>,
+    status: u16,
+}
+
+impl Default for MemoryTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryTransport {
+    /// Create a transport accepting every batch
+    pub fn new() -> Self {
+        Self::with_status(200)
+    }
+
+    /// Create a transport answering every batch with `status`
+    ///
+    /// Handy to test how a game reacts to 401s, 429s or 5xx errors.
+    pub fn with_status(status: u16) -> Self {
+        Self {
+            batches: Mutex::new(Vec::new()),
+            status,
+        }
+    }
+
+    /// Batches received so far
+    pub fn batches(&self) -> Vec<Vec<GameEventsIOEvent>> {
+        self.lock().clone()
+    }
+
+    /// All events received so far, in order
+    pub fn events(&self) -> Vec<GameEventsIOEvent> {
+        self.lock().iter().flatten().cloned().collect()
+    }
+
+    /// Forget the received batches
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Vec<GameEventsIOEvent>>> {
+        self.batches
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, batch: &[GameEventsIOEvent]) -> Result<TransportResponse, GameEventsIOError> {
+        self.lock().push(batch.to_vec());
+        Ok(TransportResponse {
+            status: self.status,
+            body: String::new(),
+            retry_after: None,
+            server_time: None,
+        })
+    }
+}
+
+/// Transport appending events to a JSON lines file, one event per line
+#[derive(Clone, Debug)]
+pub struct FileTransport {
+    path: PathBuf,
+}
+
+impl FileTransport {
+    /// Append events to the file at `path`, creating it if needed
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Transport for FileTransport {
+    fn send(&self, batch: &[GameEventsIOEvent]) -> Result<TransportResponse, GameEventsIOError> {
+        let mut lines = Vec::new();
+        for event in batch {
+            let payload = WireVersion::V1
+                .to_value(event)
+                .map_err(std::io::Error::from)?;
+            serde_json::to_writer(&mut lines, &payload).map_err(std::io::Error::from)?;
+            lines.push(b'\n');
+        }
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(&lines)?;
+
+        Ok(TransportResponse {
+            status: 200,
+            body: self.path.display().to_string(),
+            retry_after: None,
+            server_time: None,
+        })
+    }
+}
+
+/// Transport passing on only the events with (or without) a tag
+///
+/// Events filtered out count as delivered and are not retried.
+#[derive(Clone, Debug)]
+pub struct TagFilter<T> {
+    inner: T,
+    tag: String,
+    keep_tagged: bool,
+}
+
+impl<T: Transport> TagFilter<T> {
+    /// Send only the events tagged with `tag` through `inner`
+    pub fn only(inner: T, tag: impl Into<String>) -> Self {
+        Self {
+            inner,
+            tag: tag.into(),
+            keep_tagged: true,
+        }
+    }
+
+    /// Send only the events not tagged with `tag` through `inner`
+    pub fn except(inner: T, tag: impl Into<String>) -> Self {
+        Self {
+            inner,
+            tag: tag.into(),
+            keep_tagged: false,
+        }
+    }
+}
+
+impl<T: Transport> TagFilter<T> {
+    /// Send the selected events of `batch` with `send`
+    fn send_selected(
+        &self,
+        batch: &[GameEventsIOEvent],
+        send: impl FnOnce(&[GameEventsIOEvent]) -> Result<TransportResponse, GameEventsIOError>,
+    ) -> Result<TransportResponse, GameEventsIOError> {
+        let (positions, selected): (Vec<usize>, Vec<GameEventsIOEvent>) = batch
+            .iter()
+            .enumerate()
+            .filter(|(_, event)| event.has_tag(&self.tag) == self.keep_tagged)
+            .map(|(position, event)| (position, event.clone()))
+            .unzip();
+        if selected.is_empty() {
+            return Ok(TransportResponse {
+                status: 200,
+                ..TransportResponse::default()
+            });
+        }
+
+        let mut response = send(&selected)?;
+
+        // Rejected indices refer to the filtered batch; map them back
+        if let Ok(serde_json::Value::Object(mut report)) = serde_json::from_str(&response.body) {
+            if let Some(serde_json::Value::Array(indices)) = report.get_mut("rejected_indices") {
+                for index in indices.iter_mut() {
+                    if let Some(&position) = index.as_u64().and_then(|i| positions.get(i as usize))
+                    {
+                        *index = position.into();
+                    }
+                }
+                response.body = serde_json::Value::Object(report).to_string();
+            }
+        }
+        Ok(response)
+    }
+}
+
+impl<T: Transport> Transport for TagFilter<T> {
+    fn send(&self, batch: &[GameEventsIOEvent]) -> Result<TransportResponse, GameEventsIOError> {
+        self.send_selected(batch, |selected| self.inner.send(selected))
+    }
+
+    fn send_within(
+        &self,
+        batch: &[GameEventsIOEvent],
+        timeout: Duration,
+    ) -> Result<TransportResponse, GameEventsIOError> {
+        self.send_selected(batch, |selected| self.inner.send_within(selected, timeout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16, body: &str) -> TransportResponse {
+        TransportResponse {
+            status,
+            body: body.to_string(),
+            retry_after: None,
+            server_time: None,
+        }
+    }
+
+    #[test]
+    fn test_flush_result_from_response() {
+        let result = FlushResult::from_response(response(200, "ok"), 3).unwrap();
+        assert_eq!((result.accepted, result.rejected), (3, 0));
+
+        let result =
+            FlushResult::from_response(response(200, r#"{"rejected_indices":[1]}"#), 3).unwrap();
+        assert_eq!((result.accepted, result.rejected), (2, 1));
+        assert_eq!(result.rejected_indices, vec![1]);
+
+        assert!(matches!(
+            FlushResult::from_response(response(401, ""), 3),
+            Err(GameEventsIOError::Unauthorized)
+        ));
+        match FlushResult::from_response(response(422, r#"{"rejected_indices":[0,2]}"#), 3) {
+            Err(GameEventsIOError::InvalidEvents { rejected_indices }) => {
+                assert_eq!(rejected_indices, vec![0, 2])
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(matches!(
+            FlushResult::from_response(response(503, "down"), 3),
+            Err(GameEventsIOError::Server { status: 503, .. })
+        ));
+    }
+
+    fn test_event(name: &str) -> GameEventsIOEvent {
+        crate::GameEventsIOEventBuilder::default()
+            .event(name)
+            .user_id("user123")
+            .session_id("session456")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_memory_transport() {
+        let transport = Arc::new(MemoryTransport::new());
+        let mut client = crate::GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+        client.log_event(test_event("first"));
+        client.log_event(test_event("second"));
+        client.flush_batch(1).unwrap();
+
+        assert_eq!(transport.batches().len(), 1);
+        assert_eq!(transport.events()[0].event, "first");
+
+        let failing = MemoryTransport::with_status(401);
+        assert_eq!(failing.send(&[test_event("x")]).unwrap().status, 401);
+    }
+
+    #[test]
+    fn test_file_transport_appends_lines() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", uuid::Uuid::new_v4()));
+        let transport = FileTransport::new(dir.join("events.jsonl"));
+
+        transport.send(&[test_event("first")]).unwrap();
+        transport
+            .send(&[test_event("second"), test_event("third")])
+            .unwrap();
+
+        let contents = fs::read_to_string(dir.join("events.jsonl")).unwrap();
+        let names: Vec<String> = contents
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<GameEventsIOEvent>(line)
+                    .unwrap()
+                    .event
+            })
+            .collect();
+        assert_eq!(names, vec!["first", "second", "third"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_http_date() {
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(784_111_777)
+        );
+        assert_eq!(parse_http_date("Thu, 01 Jan 1970 00:00:00 GMT"), Some(0));
+        assert_eq!(parse_http_date("yesterday"), None);
+    }
+}
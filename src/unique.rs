@@ -0,0 +1,161 @@
+//! "First time today" tracking.
+//!
+//! `UniqueTracker` remembers which occurrences (first level play, first
+//! shop visit, ...) have already happened on the current day and emits a
+//! `<name>_first_daily` event the first time each one happens.
+
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{storage, unix_now, GameEventsIOSession};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Daily dedupe for "first time today" events
+#[derive(Clone, Debug, Default)]
+pub struct UniqueTracker {
+    /// Storage directory the seen set is persisted to, if any
+    storage_dir: Option<PathBuf>,
+
+    /// Offset from UTC used to decide where a day starts, in seconds
+    utc_offset_secs: i64,
+
+    state: TrackerState,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct TrackerState {
+    /// Day number the seen set belongs to
+    day: i64,
+
+    /// Occurrences already recorded on `day`
+    seen: BTreeSet<String>,
+}
+
+impl UniqueTracker {
+    /// Create a tracker that forgets everything on restart
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load a tracker persisted in the SDK storage directory
+    pub fn load(storage_dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let storage_dir = storage_dir.into();
+        let state = storage::UNIQUE_DAILY
+            .load(&storage_dir)?
+            .unwrap_or_default();
+
+        Ok(Self {
+            storage_dir: Some(storage_dir),
+            utc_offset_secs: 0,
+            state,
+        })
+    }
+
+    /// Start days at local midnight for the given UTC offset in seconds
+    pub fn with_utc_offset(mut self, utc_offset_secs: i64) -> Self {
+        self.utc_offset_secs = utc_offset_secs;
+        self
+    }
+
+    /// Record an occurrence, emitting `<name>_first_daily` if it is the first today
+    ///
+    /// Returns whether this was the first occurrence today.
+    pub fn record(&mut self, session: &GameEventsIOSession, name: &str) -> io::Result<bool> {
+        self.record_at(session, name, unix_now())
+    }
+
+    /// Same as `record`, with an explicit Unix timestamp in seconds
+    pub fn record_at(
+        &mut self,
+        session: &GameEventsIOSession,
+        name: &str,
+        now: u64,
+    ) -> io::Result<bool> {
+        self.roll_day(now);
+        if !self.state.seen.insert(name.to_string()) {
+            return Ok(false);
+        }
+
+        session.push_event(format!("{}_first_daily", name), HashMap::new());
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Check whether an occurrence was already recorded today
+    pub fn seen_today(&self, name: &str, now: u64) -> bool {
+        self.state.day == self.day_of(now) && self.state.seen.contains(name)
+    }
+
+    fn day_of(&self, now: u64) -> i64 {
+        (now as i64 + self.utc_offset_secs).div_euclid(SECONDS_PER_DAY)
+    }
+
+    fn roll_day(&mut self, now: u64) {
+        let day = self.day_of(now);
+        if day != self.state.day {
+            self.state.day = day;
+            self.state.seen.clear();
+        }
+    }
+
+    fn save(&self) -> io::Result<()> {
+        match &self.storage_dir {
+            Some(dir) => storage::UNIQUE_DAILY.save(dir, &self.state),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const DAY_START: u64 = 1_700_006_400; // 2023-11-15T00:00:00Z
+
+    #[test]
+    fn test_first_daily_emitted_once_per_day() {
+        let session = GameEventsIOSession::new("user123", "session456");
+        let mut tracker = UniqueTracker::in_memory();
+
+        assert!(tracker
+            .record_at(&session, "shop_visit", DAY_START + 10)
+            .unwrap());
+        assert!(!tracker
+            .record_at(&session, "shop_visit", DAY_START + 20)
+            .unwrap());
+        assert!(tracker.seen_today("shop_visit", DAY_START + 30));
+
+        // A new day resets the dedupe
+        assert!(tracker
+            .record_at(&session, "shop_visit", DAY_START + 86_400)
+            .unwrap());
+
+        // new_session + two first_daily events
+        let events = session.take_events(10);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[1].event, "shop_visit_first_daily");
+    }
+
+    #[test]
+    fn test_dedupe_persists() {
+        let dir = std::env::temp_dir().join(format!("game-events-sdk-{}", uuid::Uuid::new_v4()));
+        let session = GameEventsIOSession::new("user123", "session456");
+
+        let mut tracker = UniqueTracker::load(&dir).unwrap();
+        assert!(tracker
+            .record_at(&session, "level_play", DAY_START)
+            .unwrap());
+
+        let mut tracker = UniqueTracker::load(&dir).unwrap();
+        assert!(!tracker
+            .record_at(&session, "level_play", DAY_START + 60)
+            .unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,397 @@
+//! Validation of events before they are buffered.
+//!
+//! The backend silently drops events it can't ingest. `validate` checks an
+//! event against the backend's limits locally, and a `SchemaRegistry` on the
+//! client adds required properties per event name, so problems surface at
+//! log time with a precise reason.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::entitlements::OWNED_DLC_PROPERTY;
+use crate::{summary, GameEventsIOEvent};
+
+/// Limits the backend enforces on every event
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EventLimits {
+    /// Longest accepted event name, in bytes (default: 64)
+    pub max_name_len: usize,
+
+    /// Most event properties per event (default: 100)
+    pub max_properties: usize,
+
+    /// Largest property value, in bytes of JSON (default: 1024)
+    pub max_value_len: usize,
+
+    /// Whether arrays and objects are accepted as event property values
+    /// (default: false)
+    pub allow_nested: bool,
+}
+
+impl Default for EventLimits {
+    fn default() -> Self {
+        Self {
+            max_name_len: 64,
+            max_properties: 100,
+            max_value_len: 1024,
+            allow_nested: false,
+        }
+    }
+}
+
+/// Expected type of a property value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PropertyType {
+    /// A JSON string
+    String,
+
+    /// A JSON number
+    Number,
+
+    /// `true` or `false`
+    Bool,
+
+    /// Any value
+    Any,
+}
+
+impl PropertyType {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            PropertyType::String => value.is_string(),
+            PropertyType::Number => value.is_number(),
+            PropertyType::Bool => value.is_boolean(),
+            PropertyType::Any => true,
+        }
+    }
+}
+
+/// Why an event failed validation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The event name is empty
+    EmptyName,
+
+    /// The event name is longer than `EventLimits::max_name_len`
+    NameTooLong {
+        /// Length of the name in bytes
+        len: usize,
+        /// Longest accepted name
+        max: usize,
+    },
+
+    /// The event name contains a character other than `A-Z a-z 0-9 _ - . :`
+    InvalidNameChar(char),
+
+    /// The event has more than `EventLimits::max_properties` event properties
+    TooManyProperties {
+        /// Number of event properties
+        count: usize,
+        /// Most accepted properties
+        max: usize,
+    },
+
+    /// A property value is larger than `EventLimits::max_value_len`
+    ValueTooLong {
+        /// Property key
+        key: String,
+        /// Size of the value in bytes of JSON
+        len: usize,
+        /// Largest accepted value
+        max: usize,
+    },
+
+    /// A property value is an array or object (see `EventLimits::allow_nested`)
+    NestedValue(String),
+
+    /// A property required by the event's schema is missing
+    MissingProperty(String),
+
+    /// A property has a different type than the event's schema requires
+    WrongType {
+        /// Property key
+        key: String,
+        /// Type required by the schema
+        expected: PropertyType,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::EmptyName => write!(f, "event name is empty"),
+            ValidationError::NameTooLong { len, max } => {
+                write!(f, "event name is {} bytes, at most {} allowed", len, max)
+            }
+            ValidationError::InvalidNameChar(c) => {
+                write!(f, "event name contains invalid character {:?}", c)
+            }
+            ValidationError::TooManyProperties { count, max } => {
+                write!(f, "{} event properties, at most {} allowed", count, max)
+            }
+            ValidationError::ValueTooLong { key, len, max } => {
+                write!(
+                    f,
+                    "property {:?} is {} bytes, at most {} allowed",
+                    key, len, max
+                )
+            }
+            ValidationError::NestedValue(key) => {
+                write!(f, "property {:?} is not a primitive value", key)
+            }
+            ValidationError::MissingProperty(key) => {
+                write!(f, "required property {:?} is missing", key)
+            }
+            ValidationError::WrongType { key, expected } => {
+                write!(f, "property {:?} should be {:?}", key, expected)
+            }
+        }
+    }
+}
+
+/// Required properties of one event type
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventSchema {
+    required: Vec<(String, PropertyType)>,
+}
+
+impl EventSchema {
+    /// Schema without required properties
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require an event property of the given type
+    pub fn require(mut self, key: impl Into<String>, property_type: PropertyType) -> Self {
+        self.required.push((key.into(), property_type));
+        self
+    }
+
+    fn check(&self, event: &GameEventsIOEvent, errors: &mut Vec<ValidationError>) {
+        for (key, expected) in &self.required {
+            match event.event_properties.get(key) {
+                None => errors.push(ValidationError::MissingProperty(key.clone())),
+                Some(value) if !expected.matches(value) => {
+                    errors.push(ValidationError::WrongType {
+                        key: key.clone(),
+                        expected: *expected,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+/// Limits plus per-event-name schemas, checked by the client at log time
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SchemaRegistry {
+    limits: EventLimits,
+    schemas: HashMap<String, EventSchema>,
+}
+
+impl SchemaRegistry {
+    /// Registry checking the default limits only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check against different limits
+    pub fn limits(mut self, limits: EventLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Register the schema of events named `event`
+    pub fn schema(mut self, event: impl Into<String>, schema: EventSchema) -> Self {
+        self.schemas.insert(event.into(), schema);
+        self
+    }
+
+    /// Check an event, returning every problem found
+    pub fn validate(&self, event: &GameEventsIOEvent) -> Result<(), Vec<ValidationError>> {
+        let mut errors = check_limits(event, &self.limits);
+        if let Some(schema) = self.schemas.get(&event.event) {
+            schema.check(event, &mut errors);
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl GameEventsIOEvent {
+    /// Check the event against the backend's default limits
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        SchemaRegistry::new().validate(self)
+    }
+}
+
+fn check_limits(event: &GameEventsIOEvent, limits: &EventLimits) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    let name = &event.event;
+    if name.is_empty() {
+        errors.push(ValidationError::EmptyName);
+    }
+    if name.len() > limits.max_name_len {
+        errors.push(ValidationError::NameTooLong {
+            len: name.len(),
+            max: limits.max_name_len,
+        });
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')))
+    {
+        errors.push(ValidationError::InvalidNameChar(c));
+    }
+
+    let count = event.event_properties.len();
+    if count > limits.max_properties {
+        errors.push(ValidationError::TooManyProperties {
+            count,
+            max: limits.max_properties,
+        });
+    }
+
+    // Only event properties must be flat: user properties carry the SDK's
+    // own arrays (owned DLC), and so do the aggregates of `session_summary`
+    let flat = !limits.allow_nested && event.event != summary::EVENT_SESSION_SUMMARY;
+
+    // Sorted so errors come out in a stable order
+    let mut properties: Vec<(&String, &Value, bool)> = event
+        .event_properties
+        .iter()
+        .map(|(key, value)| (key, value, flat))
+        .chain(
+            event
+                .user_properties
+                .iter()
+                .filter(|(key, _)| key.as_str() != OWNED_DLC_PROPERTY)
+                .map(|(key, value)| (key, value, false)),
+        )
+        .collect();
+    properties.sort_unstable_by_key(|(key, _, _)| *key);
+    for (key, value, flat) in properties {
+        if flat && (value.is_array() || value.is_object()) {
+            errors.push(ValidationError::NestedValue(key.clone()));
+        }
+        let len = value.to_string().len();
+        if len > limits.max_value_len {
+            errors.push(ValidationError::ValueTooLong {
+                key: key.clone(),
+                len,
+                max: limits.max_value_len,
+            });
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Entitlements, GameEventsIOClientBuilder, GameEventsIOEventBuilder,
+        GameEventsIOSessionBuilder, MemoryTransport,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_event() {
+        let event = GameEventsIOEventBuilder::default()
+            .event("level completed!")
+            .event_properties(HashMap::from([
+                ("position".to_string(), json!({ "x": 1 })),
+                ("note".to_string(), json!("x".repeat(2000))),
+            ]))
+            .build()
+            .unwrap();
+        assert_eq!(
+            event.validate().unwrap_err(),
+            [
+                ValidationError::InvalidNameChar(' '),
+                ValidationError::ValueTooLong {
+                    key: "note".to_string(),
+                    len: 2002,
+                    max: 1024,
+                },
+                ValidationError::NestedValue("position".to_string()),
+            ]
+        );
+
+        let registry = SchemaRegistry::new()
+            .limits(EventLimits {
+                allow_nested: true,
+                ..EventLimits::default()
+            })
+            .schema(
+                "purchase",
+                EventSchema::new()
+                    .require("item_id", PropertyType::String)
+                    .require("price", PropertyType::Number),
+            );
+        let purchase = GameEventsIOEventBuilder::default()
+            .event("purchase")
+            .event_properties(HashMap::from([("price".to_string(), json!("9.99"))]))
+            .build()
+            .unwrap();
+        assert_eq!(
+            registry.validate(&purchase).unwrap_err(),
+            [
+                ValidationError::MissingProperty("item_id".to_string()),
+                ValidationError::WrongType {
+                    key: "price".to_string(),
+                    expected: PropertyType::Number,
+                },
+            ]
+        );
+        assert!(registry
+            .validate(
+                &GameEventsIOEvent::purchase("gem_pack", 4.99, "USD")
+                    .build()
+                    .unwrap()
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_sdk_properties_pass_validation() {
+        let mut client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(MemoryTransport::new())
+            .schema_registry(SchemaRegistry::new())
+            .build()
+            .unwrap();
+        let session = GameEventsIOSessionBuilder::default()
+            .user_id("user123")
+            .entitlement_provider(|| Entitlements {
+                owned_dlc: vec!["frozen_north".to_string()],
+                ..Entitlements::default()
+            })
+            .emit_summary()
+            .build()
+            .unwrap();
+        session.push_event(
+            "purchase",
+            GameEventsIOEvent::purchase("gem_pack", 4.99, "USD")
+                .build()
+                .unwrap()
+                .event_properties,
+        );
+        session.end_session();
+
+        let events = session.take_events(100);
+        let count = events.len();
+        for event in events {
+            client.try_log_event(event).unwrap();
+        }
+        assert_eq!(client.pending_events_count(), count);
+    }
+}
@@ -0,0 +1,278 @@
+//! Stable wire format of events.
+//!
+//! The payload structs here are the only types serialized for the backend.
+//! They are decoupled from `GameEventsIOEvent` and converted explicitly, so
+//! adding, renaming or retyping an event field can't change what is sent;
+//! only a change to this module can, and the golden payloads in
+//! `tests/golden` catch it.
+//!
+//! - `WirePayloadV1`: timestamps in seconds. Sent by default.
+//! - `WirePayloadV2`: timestamps in milliseconds (`time_ms`,
+//!   `client_upload_time_ms`).
+//!
+//! Both carry the client's sequence number (`seq`) when it is set.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{GameEventsIOEvent, PurchaseReceipt};
+
+/// Version of the payload format sent to the backend
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WireVersion {
+    /// `WirePayloadV1`
+    #[default]
+    V1,
+
+    /// `WirePayloadV2`
+    V2,
+}
+
+impl WireVersion {
+    /// Payload of one event in this version
+    pub fn to_value(self, event: &GameEventsIOEvent) -> serde_json::Result<Value> {
+        match self {
+            WireVersion::V1 => serde_json::to_value(WirePayloadV1::from_event(event)),
+            WireVersion::V2 => serde_json::to_value(WirePayloadV2::from_event(event)),
+        }
+    }
+
+    /// Write the payload of one event, followed by a newline (NDJSON)
+    pub fn write_line(
+        self,
+        event: &GameEventsIOEvent,
+        mut writer: impl Write,
+    ) -> serde_json::Result<()> {
+        match self {
+            WireVersion::V1 => {
+                serde_json::to_writer(&mut writer, &WirePayloadV1::from_event(event))
+            }
+            WireVersion::V2 => {
+                serde_json::to_writer(&mut writer, &WirePayloadV2::from_event(event))
+            }
+        }?;
+        writer.write_all(b"\n").map_err(serde_json::Error::io)
+    }
+
+    /// JSON array of the payloads of a batch
+    pub fn encode_batch(self, batch: &[GameEventsIOEvent]) -> serde_json::Result<Vec<u8>> {
+        match self {
+            WireVersion::V1 => serde_json::to_vec(
+                &batch
+                    .iter()
+                    .map(WirePayloadV1::from_event)
+                    .collect::<Vec<_>>(),
+            ),
+            WireVersion::V2 => serde_json::to_vec(
+                &batch
+                    .iter()
+                    .map(WirePayloadV2::from_event)
+                    .collect::<Vec<_>>(),
+            ),
+        }
+    }
+}
+
+/// Event payload, version 1
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct WirePayloadV1 {
+    /// Event name
+    pub event: String,
+
+    /// User identifier
+    pub user_id: String,
+
+    /// Session identifier
+    pub session_id: String,
+
+    /// Unix timestamp in seconds
+    pub time: u64,
+
+    /// Position of the event among the ones logged by the client
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+
+    /// Event-specific properties
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub event_properties: HashMap<String, Value>,
+
+    /// User properties
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub user_properties: HashMap<String, Value>,
+
+    /// Unix timestamp in seconds when the batch was sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_upload_time: Option<u64>,
+
+    /// Free-form labels
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Store receipt of a purchase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<PurchaseReceipt>,
+
+    /// Fields unknown to this SDK version, passed through unchanged
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl WirePayloadV1 {
+    /// Payload of an event
+    pub fn from_event(event: &GameEventsIOEvent) -> Self {
+        Self {
+            event: event.event.clone(),
+            user_id: event.user_id.clone(),
+            session_id: event.session_id.clone(),
+            time: event.time,
+            seq: event.seq,
+            event_properties: event.event_properties.clone(),
+            user_properties: event.user_properties.clone(),
+            client_upload_time: event.client_upload_time,
+            tags: event.tags.clone(),
+            receipt: event.receipt.clone(),
+            extra: event.extra.clone(),
+        }
+    }
+
+    /// Event described by the payload
+    pub fn into_event(self) -> GameEventsIOEvent {
+        GameEventsIOEvent {
+            event: self.event,
+            user_id: self.user_id,
+            session_id: self.session_id,
+            time: self.time,
+            seq: self.seq,
+            event_properties: self.event_properties,
+            user_properties: self.user_properties,
+            client_upload_time: self.client_upload_time,
+            tags: self.tags,
+            receipt: self.receipt,
+            extra: self.extra,
+            ..GameEventsIOEvent::default()
+        }
+    }
+}
+
+/// Event payload, version 2: timestamps in milliseconds
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct WirePayloadV2 {
+    /// Event name
+    pub event: String,
+
+    /// User identifier
+    pub user_id: String,
+
+    /// Session identifier
+    pub session_id: String,
+
+    /// Unix timestamp in milliseconds
+    pub time_ms: u64,
+
+    /// Position of the event among the ones logged by the client
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+
+    /// Event-specific properties
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub event_properties: HashMap<String, Value>,
+
+    /// User properties
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub user_properties: HashMap<String, Value>,
+
+    /// Unix timestamp in milliseconds when the batch was sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_upload_time_ms: Option<u64>,
+
+    /// Free-form labels
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Store receipt of a purchase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<PurchaseReceipt>,
+
+    /// Fields unknown to this SDK version, passed through unchanged
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl WirePayloadV2 {
+    /// Payload of an event
+    pub fn from_event(event: &GameEventsIOEvent) -> Self {
+        Self {
+            event: event.event.clone(),
+            user_id: event.user_id.clone(),
+            session_id: event.session_id.clone(),
+            time_ms: event.time_ms.unwrap_or(event.time * 1000),
+            seq: event.seq,
+            event_properties: event.event_properties.clone(),
+            user_properties: event.user_properties.clone(),
+            client_upload_time_ms: event
+                .client_upload_time_ms
+                .or(event.client_upload_time.map(|time| time * 1000)),
+            tags: event.tags.clone(),
+            receipt: event.receipt.clone(),
+            extra: event.extra.clone(),
+        }
+    }
+
+    /// Event described by the payload
+    pub fn into_event(self) -> GameEventsIOEvent {
+        GameEventsIOEvent {
+            event: self.event,
+            user_id: self.user_id,
+            session_id: self.session_id,
+            time: self.time_ms / 1000,
+            time_ms: Some(self.time_ms),
+            seq: self.seq,
+            event_properties: self.event_properties,
+            user_properties: self.user_properties,
+            client_upload_time: self.client_upload_time_ms.map(|time| time / 1000),
+            client_upload_time_ms: self.client_upload_time_ms,
+            tags: self.tags,
+            receipt: self.receipt,
+            extra: self.extra,
+            ..GameEventsIOEvent::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compat::{golden_corpus, GOLDEN_TIME};
+
+    #[test]
+    fn test_payload_versions() {
+        for (name, event) in golden_corpus() {
+            let payload = WirePayloadV1::from_event(&event);
+            assert_eq!(
+                serde_json::to_value(&payload).unwrap(),
+                serde_json::to_value(&event).unwrap(),
+                "{}",
+                name
+            );
+            let restored = payload.clone().into_event();
+            assert_eq!(WirePayloadV1::from_event(&restored), payload, "{}", name);
+        }
+
+        let (_, event) = golden_corpus().remove(4);
+        let value = WireVersion::V2.to_value(&event).unwrap();
+        assert_eq!(value["time_ms"], GOLDEN_TIME * 1000);
+        assert_eq!(value["client_upload_time_ms"], (GOLDEN_TIME + 30) * 1000);
+        assert!(value.get("time").is_none());
+
+        let restored: WirePayloadV2 = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            restored.into_event().client_upload_time,
+            Some(GOLDEN_TIME + 30)
+        );
+    }
+}
@@ -0,0 +1,350 @@
+//! Background auto-flush worker.
+//!
+//! `GameEventsIOWorker` owns a client on a background thread and flushes it
+//! periodically. Game code logs events into a shared buffer that is only
+//! locked briefly, so logging never waits on the network.
+
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{
+    diagnostics, lock, EventPriority, EventQuery, FlushResult, GameEventsIOClient,
+    GameEventsIOError, GameEventsIOEvent, PowerProfile,
+};
+
+/// Periodically flushes a client on a background thread
+#[derive(Debug)]
+pub struct GameEventsIOWorker {
+    /// Events logged since the last tick
+    buffer: Arc<Mutex<Vec<GameEventsIOEvent>>>,
+
+    /// Client doing the actual sending
+    client: Arc<Mutex<GameEventsIOClient>>,
+
+    /// Maximum number of events per request
+    batch_size: usize,
+
+    /// Current upload profile
+    power: Arc<Mutex<PowerProfile>>,
+
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl GameEventsIOWorker {
+    /// Move a client onto a background thread flushing every `interval`
+    pub fn start(client: GameEventsIOClient, interval: Duration, batch_size: usize) -> Self {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let client = Arc::new(Mutex::new(client));
+        let batch_size = batch_size.max(1);
+        let power = Arc::new(Mutex::new(PowerProfile::Normal));
+        let (stop, stopped) = mpsc::channel::<()>();
+
+        // Without a thread, events are only sent by `flush_now` and `shutdown`
+        let thread = {
+            let buffer = buffer.clone();
+            let client = client.clone();
+            let power = power.clone();
+            let spawned = thread::Builder::new()
+                .name("game-events-flush".to_string())
+                .spawn(move || loop {
+                    let timeout = lock(&power).interval(interval);
+                    match stopped.recv_timeout(timeout) {
+                        Err(RecvTimeoutError::Timeout) => {
+                            // Unsent events stay buffered until the next tick
+                            if let Err(e) = tick(&buffer, &client, batch_size, *lock(&power)) {
+                                diagnostics::report(&e);
+                            }
+                        }
+                        _ => break,
+                    }
+                })
+                .map(Some);
+            diagnostics::recover(spawned, "Failed to spawn flush worker thread", || None)
+        };
+
+        Self {
+            buffer,
+            client,
+            batch_size,
+            power,
+            stop: Some(stop),
+            thread,
+        }
+    }
+
+    /// Log an event (adds to the shared buffer)
+    pub fn log_event(&self, event: GameEventsIOEvent) {
+        lock(&self.buffer).push(event);
+    }
+
+    /// Flush right away on the calling thread
+    pub fn flush_now(&self) -> Result<(), GameEventsIOError> {
+        flush_pending(&self.buffer, &self.client, self.batch_size)
+    }
+
+    /// Write all events not sent yet to the offline queue (see `GameEventsIOClient::persist_now`)
+    pub fn persist_now(&self) -> std::io::Result<()> {
+        collect(&self.buffer, &self.client).persist_now()
+    }
+
+    /// Get the number of events not sent yet
+    pub fn pending_events_count(&self) -> usize {
+        // The buffer is never held while waiting for the client, so a flush
+        // in progress doesn't block `log_event`
+        let sending = lock(&self.client).pending_events_count();
+        sending + lock(&self.buffer).len()
+    }
+
+    /// Find events not sent yet, in the order they were logged
+    pub fn query(&self, query: EventQuery) -> Vec<GameEventsIOEvent> {
+        let client = lock(&self.client);
+        let buffer = lock(&self.buffer);
+        query
+            .run(client.events.iter().chain(buffer.iter()))
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Switch the upload profile (e.g. when going on battery power)
+    ///
+    /// Takes effect from the next tick. `flush_now` and `shutdown` always
+    /// send everything regardless of the profile.
+    pub fn set_power_profile(&self, profile: PowerProfile) {
+        *lock(&self.power) = profile;
+    }
+
+    /// Get the current upload profile
+    pub fn power_profile(&self) -> PowerProfile {
+        *lock(&self.power)
+    }
+
+    /// Access the underlying client (e.g. to opt out or change settings)
+    ///
+    /// Blocks while the worker is flushing.
+    pub fn client(&self) -> MutexGuard<'_, GameEventsIOClient> {
+        lock(&self.client)
+    }
+
+    /// Stop the background thread and send all remaining events
+    pub fn shutdown(mut self) -> Result<(), GameEventsIOError> {
+        self.stop_thread();
+        self.flush_now()
+    }
+
+    /// Stop the background thread and send what fits in `timeout`, critical events first
+    ///
+    /// See `GameEventsIOClient::shutdown`.
+    pub fn shutdown_within(mut self, timeout: Duration) -> Result<FlushResult, GameEventsIOError> {
+        self.stop_thread();
+        let mut client = collect(&self.buffer, &self.client);
+        client.shutdown(timeout)
+    }
+
+    fn stop_thread(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for GameEventsIOWorker {
+    fn drop(&mut self) {
+        if self.stop.is_some() {
+            self.stop_thread();
+            let _ = self.flush_now();
+        }
+    }
+}
+
+/// Move buffered events into the client, returning the locked client
+fn collect<'a>(
+    buffer: &Mutex<Vec<GameEventsIOEvent>>,
+    client: &'a Mutex<GameEventsIOClient>,
+) -> MutexGuard<'a, GameEventsIOClient> {
+    let incoming = std::mem::take(&mut *lock(buffer));
+    let mut client = lock(client);
+    for event in incoming {
+        client.log_event(event);
+    }
+    client
+}
+
+/// Move buffered events into the client and send them
+fn flush_pending(
+    buffer: &Mutex<Vec<GameEventsIOEvent>>,
+    client: &Mutex<GameEventsIOClient>,
+    batch_size: usize,
+) -> Result<(), GameEventsIOError> {
+    let mut client = collect(buffer, client);
+    while client.pending_events_count() > 0 {
+        client.flush_batch(batch_size)?;
+    }
+    Ok(())
+}
+
+/// Periodic flush, following the power profile
+fn tick(
+    buffer: &Mutex<Vec<GameEventsIOEvent>>,
+    client: &Mutex<GameEventsIOClient>,
+    batch_size: usize,
+    profile: PowerProfile,
+) -> Result<(), GameEventsIOError> {
+    let batch_size = profile.batch_size(batch_size);
+    if !profile.defers_non_critical() {
+        return flush_pending(buffer, client, batch_size);
+    }
+
+    // Critical events go out right away, the rest only in full batches
+    let mut client = collect(buffer, client);
+    client.flush_priority(EventPriority::Critical)?;
+    while client.pending_events_count() >= batch_size {
+        client.flush_batch(batch_size)?;
+    }
+    Ok(())
+}
+
+impl GameEventsIOClient {
+    /// Move this client onto a background thread that flushes it periodically
+    pub fn start_auto_flush(self, interval: Duration, batch_size: usize) -> GameEventsIOWorker {
+        GameEventsIOWorker::start(self, interval, batch_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::{Transport, TransportResponse};
+    use crate::{GameEventsIOClientBuilder, GameEventsIOEventBuilder};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Instant;
+
+    #[derive(Default)]
+    struct CountingTransport {
+        sent: AtomicUsize,
+    }
+
+    impl Transport for CountingTransport {
+        fn send(
+            &self,
+            batch: &[GameEventsIOEvent],
+        ) -> Result<TransportResponse, GameEventsIOError> {
+            self.sent.fetch_add(batch.len(), Ordering::SeqCst);
+            Ok(TransportResponse {
+                status: 200,
+                body: String::new(),
+                retry_after: None,
+                server_time: None,
+            })
+        }
+    }
+
+    fn test_event() -> GameEventsIOEvent {
+        GameEventsIOEventBuilder::default()
+            .event("test_event")
+            .user_id("user123")
+            .session_id("session456")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_worker_flushes_periodically() {
+        let transport = Arc::new(CountingTransport::default());
+        let client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+        let worker = client.start_auto_flush(Duration::from_millis(10), 2);
+
+        for _ in 0..5 {
+            worker.log_event(test_event());
+        }
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while transport.sent.load(Ordering::SeqCst) < 5 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(transport.sent.load(Ordering::SeqCst), 5);
+        assert_eq!(worker.pending_events_count(), 0);
+    }
+
+    #[test]
+    fn test_low_power_defers_non_critical() {
+        let transport = Arc::new(CountingTransport::default());
+        let client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+        let buffer = Mutex::new(Vec::new());
+        let client = Mutex::new(client);
+
+        let mut purchase = test_event();
+        purchase.event = "purchase".to_string();
+        lock(&buffer).push(purchase);
+        for _ in 0..3 {
+            lock(&buffer).push(test_event());
+        }
+
+        // Batch size 1 becomes 4 in low-power mode: only the purchase goes out
+        tick(&buffer, &client, 1, PowerProfile::LowPower).unwrap();
+        assert_eq!(transport.sent.load(Ordering::SeqCst), 1);
+        assert_eq!(lock(&client).pending_events_count(), 3);
+
+        // A fourth regular event fills a low-power batch
+        lock(&buffer).push(test_event());
+        tick(&buffer, &client, 1, PowerProfile::LowPower).unwrap();
+        assert_eq!(transport.sent.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn test_worker_shutdown_drains() {
+        let transport = Arc::new(CountingTransport::default());
+        let client = GameEventsIOClientBuilder::default()
+            .api_key("test_api_key")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+        let worker = client.start_auto_flush(Duration::from_secs(3600), 100);
+
+        for _ in 0..3 {
+            worker.log_event(test_event());
+        }
+        worker.shutdown().unwrap();
+
+        assert_eq!(transport.sent.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_logging_while_client_is_busy() {
+        let worker = Arc::new(
+            GameEventsIOClient::new("test_api_key")
+                .start_auto_flush(Duration::from_secs(3600), 100),
+        );
+
+        // Readers wait for the client (e.g. a flush) without holding the buffer
+        let held = worker.client();
+        let count = {
+            let worker = worker.clone();
+            thread::spawn(move || worker.pending_events_count())
+        };
+        let found = {
+            let worker = worker.clone();
+            thread::spawn(move || worker.query(EventQuery::all()).len())
+        };
+        thread::sleep(Duration::from_millis(20));
+        worker.log_event(test_event());
+        drop(held);
+
+        assert_eq!(count.join().unwrap(), 1);
+        assert_eq!(found.join().unwrap(), 1);
+    }
+}
@@ -0,0 +1,29 @@
+//! Guards the event wire format against accidental changes.
+//!
+//! Run with `GAME_EVENTS_UPDATE_GOLDEN=1` to re-record the golden payloads
+//! after an intentional format change.
+
+use std::path::Path;
+
+use game_events_sdk::compat::{check_golden_dir, golden_corpus, write_golden_dir};
+
+#[test]
+fn test_wire_format_matches_golden_payloads() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let corpus = golden_corpus();
+
+    if std::env::var_os("GAME_EVENTS_UPDATE_GOLDEN").is_some() {
+        write_golden_dir(&dir, &corpus).unwrap();
+    }
+
+    let mismatches = check_golden_dir(&dir, &corpus).unwrap();
+    for mismatch in &mismatches {
+        if mismatch.missing_golden {
+            eprintln!("{}: no golden payload recorded", mismatch.name);
+        }
+        for diff in &mismatch.diffs {
+            eprintln!("{}: {}", mismatch.name, diff);
+        }
+    }
+    assert!(mismatches.is_empty(), "wire format changed");
+}